@@ -2,7 +2,7 @@ extern crate menu_glium_api as menu_api;
 use menu_api::Menu;
 
 use std::io::Error;
-use menu_api::{ windows_api, filled_box, rusttype, check_box, float_slider, label, outline_box, line_strip, Vertex };
+use menu_api::{ windows_api, overlay, run_loop, system_fonts, filled_box, check_box, float_slider, label, outline_box, line_strip, shape, Vertex };
 use windows::Win32::UI::Input::KeyboardAndMouse::{ GetAsyncKeyState };
 
 use std::cell::RefCell;
@@ -10,90 +10,85 @@ use std::rc::Rc;
 
 fn main() -> Result<(), Error> {
     // optional
-    let hwnd = windows_api::grab_game_hwnd("Counter-Strike 2");         // Some(hwnd)
-    let (event_loop, window, display, overlay_hwnd) = menu_api::create_overlay(Some(hwnd), "Black Overlay").unwrap();
+    let hwnd = windows_api::try_grab_game_hwnd("Counter-Strike 2")?;
+    let overlay = overlay::OverlayBuilder::new("Black Overlay")
+        .attach_to(hwnd)
+        // follow the game window around
+        .track_target(true)
+        .build()?;
 
-    let system = rusttype::TextSystem::new(&display);
-
-    let font = rusttype::FontTexture::new(
-        &display,
-        &include_bytes!("../fonts/arialbd.ttf")[..], 70,
-        rusttype::FontTexture::ascii_character_list()
-    ).unwrap();
+    // the bundled DejaVu Sans Bold when Arial isn't installed
+    let font = system_fonts::FontQuery::new("Arial")
+        .with_weight(system_fonts::FontWeight::Bold)
+        .load_or_default();
+    let mut menu = overlay.create_menu(&font, 70, (600.0, 450.0))?;
+    let overlay::Overlay { event_loop, window, .. } = overlay;
 
     let black = Rc::new(RefCell::new(false));
     let float = Rc::new(RefCell::new(10.0));
 
-    let mut menu = menu_api::Menu::new(display, system, font, overlay_hwnd, (600.0, 450.0));
-
     build_menu(&mut menu, Rc::clone(&black), Rc::clone(&float));
 
-    #[allow(deprecated)]
-    event_loop.run(move |event, window_target| {
-        match event {
-            glium::winit::event::Event::WindowEvent { event, .. } => match event {
-                // This event is sent by the OS when you close the Window, or request the program to quit via the taskbar.
-                glium::winit::event::WindowEvent::CloseRequested => window_target.exit(),
-                glium::winit::event::WindowEvent::CursorMoved { position, .. } => {
-                    menu.mouse_pos = (position.x as f32, position.y as f32);
-                },
-                glium::winit::event::WindowEvent::RedrawRequested => {
-                    let line_strip = line_strip::LineStrip::new(
-                        menu_api::MenuOptions::new(false, false, true, false),
-                        vec![
-                            Vertex { p: [ 500.0, 500.0 ] },
-                            Vertex { p: [ 550.0, 550.0 ] },
-                            Vertex { p: [ 600.0, 400.0 ] },
-                            Vertex { p: [ 400.0, 100.0 ] },
-                        ],
-                        menu_api::Vec4::new(0.0, 1.0, 1.0, 1.0),
-                        10.0
-                    );
-                    menu.add_to_draw_list(menu_api::MenuObject::LineStrip(line_strip));
-                    if *black.borrow() {
-                        println!("We are black!");
-                    }
-                    println!("{}", *float.borrow());
-                    cheat_loop(&mut menu);
-                    window.request_redraw()
-                },
-                _ => (),
-            },
-            _ => (),
-        };
-    })
-    .unwrap();
+    menu.pacing = run_loop::FramePacing::MatchRefreshRate;
+    menu.idle_when_hidden = true;
+
+    menu.run(event_loop, window, move |menu, _ctx| {
+        let line_strip = line_strip::LineStrip::new(
+            menu_api::MenuOptions::new(false, false, true, false),
+            vec![
+                Vertex { p: [ 500.0, 500.0 ] },
+                Vertex { p: [ 550.0, 550.0 ] },
+                Vertex { p: [ 600.0, 400.0 ] },
+                Vertex { p: [ 400.0, 100.0 ] },
+            ],
+            menu_api::Vec4::new(0.0, 1.0, 1.0, 1.0),
+            10.0
+        );
+        menu.add_to_draw_list(menu_api::MenuObject::LineStrip(line_strip));
+        let fov = shape::Shape::outlined(
+            menu_api::MenuOptions::new(false, false, true, false),
+            shape::ShapeKind::Circle { center: Vertex { p: [ menu.window_size.0 / 2.0, menu.window_size.1 / 2.0 ] }, radius: 100.0 },
+            menu_api::Vec4::new(1.0, 1.0, 1.0, 0.8),
+            2.0
+        );
+        menu.add_to_draw_list(menu_api::MenuObject::Shape(fov));
+        if *black.borrow() {
+            println!("We are black!");
+        }
+        println!("{}", *float.borrow());
+        cheat_loop(menu);
+    })?;
 
     Ok(())
 }
 
 fn build_menu(menu: &mut Menu, black: Rc<RefCell<bool>>, float: Rc<RefCell<f32>>) {
     let esp = label::Label::new(
-        menu_api::Vec4::new(1.0, 0.0, 0.0, 1.0),
-        label::Direction::Top,
+        Some(menu_api::Vec4::new(1.0, 0.0, 0.0, 1.0)),
+        label::Anchor::Top,
         "Esp",
         20.0,
-        0.10,
+        (0.0, -3.0),
     );
     let smoothing = label::Label::new(
-        menu_api::Vec4::new(1.0, 1.0, 0.0, 1.0),
-        label::Direction::Bottom,
+        Some(menu_api::Vec4::new(1.0, 1.0, 0.0, 1.0)),
+        label::Anchor::Bottom,
         "smoothing",
         20.0,
-        0.5,
+        (0.0, 4.0),
     );
     let fanboy = label::Label::new(
-        menu_api::Vec4::new(1.0, 1.0, 0.0, 1.0),
-        label::Direction::Top,
+        Some(menu_api::Vec4::new(1.0, 1.0, 0.0, 1.0)),
+        label::Anchor::Top,
         "Fanboy",
         20.0,
-        0.5,
+        (0.0, -4.0),
     );
     let check_box = check_box::CheckBox::new(
         menu_api::MenuOptions::new(true, true, false, true),
         menu_api::Rect::new(menu_api::Vertex { p: [ menu.base.rect.top_left.p[0] + 15.0,
                                                     menu.base.rect.top_left.p[1] + 15.0] }, 30.0, 30.0 ),
-        menu_api::Vec4::new(0.0, 1.0, 0.7, 1.0),
+        Some(menu_api::Vec4::new(0.0, 1.0, 0.7, 1.0)),
         Rc::clone(&black),
         Some(esp)
     );
@@ -101,27 +96,27 @@ fn build_menu(menu: &mut Menu, black: Rc<RefCell<bool>>, float: Rc<RefCell<f32>>
         menu_api::MenuOptions::new(true, true, false, true),
         menu_api::Rect::new(menu_api::Vertex { p: [ menu.base.rect.top_left.p[0] + 100.0,
                                                     menu.base.rect.top_left.p[1] + 100.0] }, 100.0, 100.0 ),
-        menu_api::Vec4::new(1.0, 1.0, 0.7, 1.0),
+        Some(menu_api::Vec4::new(1.0, 1.0, 0.7, 1.0)),
         Some(label::Label::new(
-            menu_api::Vec4::new(1.0, 1.0, 0.0, 1.0),
-            label::Direction::Left,
+            Some(menu_api::Vec4::new(1.0, 1.0, 0.0, 1.0)),
+            label::Anchor::Left,
             "Fanboy",
             20.0,
-            10.0
+            (-8.0, 0.0)
         ))
     );
     let filled_box1 = filled_box::FilledBox::new(
         menu_api::MenuOptions::new(true, true, false, true),
         menu_api::Rect::new(menu_api::Vertex { p: [ menu.base.rect.top_left.p[0] + 300.0,
                                                     menu.base.rect.top_left.p[1] + 300.0] }, 100.0, 100.0 ),
-        menu_api::Vec4::new(1.0, 0.0, 1.0, 1.0),
+        Some(menu_api::Vec4::new(1.0, 0.0, 1.0, 1.0)),
         None
     );
     let slider = float_slider::FloatSlider::new(
         menu_api::MenuOptions::new(true, true, false, true),
         menu_api::Rect::new(menu_api::Vertex { p: [ menu.base.rect.top_left.p[0] + 300.0,
                                                     menu.base.rect.top_left.p[1] + 300.0] }, 100.0, 10.0 ),
-        menu_api::Vec4::new(1.0, 0.0, 1.0, 1.0),
+        Some(menu_api::Vec4::new(1.0, 0.0, 1.0, 1.0)),
         Rc::clone(&float),
         0.0,
         1000.0,
@@ -131,8 +126,8 @@ fn build_menu(menu: &mut Menu, black: Rc<RefCell<bool>>, float: Rc<RefCell<f32>>
         menu_api::MenuOptions::new(false, false, false, false),
         menu_api::Rect::new(menu_api::Vertex { p: [ menu.base.rect.top_left.p[0] + 500.0,
                                                     menu.base.rect.top_left.p[1] + 500.0] }, 100.0, 100.0 ),
-        menu_api::Vec4::new(1.0, 0.0, 1.0, 1.0),
-        4.0,
+        Some(menu_api::Vec4::new(1.0, 0.0, 1.0, 1.0)),
+        Some(4.0),
         Some(fanboy)
     );
 
@@ -144,11 +139,14 @@ fn build_menu(menu: &mut Menu, black: Rc<RefCell<bool>>, float: Rc<RefCell<f32>>
 }
 
 fn cheat_loop(menu: &mut Menu) {
-    menu.draw_menu();
     unsafe {
         // insert key
         if GetAsyncKeyState(0x2D) & 0x01 > 0  {
             menu.toggle_overlay();
         }
+        // f2 key
+        if GetAsyncKeyState(0x71) & 0x01 > 0 {
+            menu.stats.show = !menu.stats.show;
+        }
     }
 }
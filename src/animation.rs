@@ -0,0 +1,190 @@
+use std::cell::Cell;
+use std::f32::consts::PI;
+
+// seconds the built in widget animations take
+pub const HOVER_DURATION: f32 = 0.12;
+pub const TOGGLE_DURATION: f32 = 0.15;
+pub const SLIDER_DURATION: f32 = 0.08;
+pub const FADE_DURATION: f32 = 0.2;
+
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    #[default]
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineInOut,
+    // overshoots a little before settling
+    BackOut,
+}
+
+impl Easing {
+    // maps 0..1 time to 0..1 progress
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 { 2.0 * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0 }
+            },
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 { 4.0 * t * t * t } else { 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0 }
+            },
+            Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+            Easing::BackOut => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            },
+        }
+    }
+}
+
+// a value that eases towards its target over `duration` seconds
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub struct Tween {
+    from: f32,
+    to: f32,
+    elapsed: f32,
+    duration: f32,
+    easing: Easing,
+}
+
+impl Tween {
+    // starts settled at `value`
+    pub fn new(value: f32, duration: f32, easing: Easing) -> Self {
+        Self {
+            from: value,
+            to: value,
+            elapsed: duration,
+            duration,
+            easing,
+        }
+    }
+    pub fn value(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return self.to
+        }
+        let t = self.elapsed / self.duration;
+        self.from + (self.to - self.from) * self.easing.apply(t)
+    }
+    pub fn target(&self) -> f32 {
+        self.to
+    }
+    // starts a new animation from wherever the value currently is
+    pub fn set_target(&mut self, target: f32) {
+        if target != self.to {
+            self.from = self.value();
+            self.to = target;
+            self.elapsed = 0.0;
+        }
+    }
+    // jumps straight to `value` without animating
+    pub fn snap(&mut self, value: f32) {
+        self.from = value;
+        self.to = value;
+        self.elapsed = self.duration;
+    }
+    pub fn set_duration(&mut self, duration: f32) {
+        self.duration = duration;
+    }
+    pub fn set_easing(&mut self, easing: Easing) {
+        self.easing = easing;
+    }
+    pub fn update(&mut self, delta_time: f32) {
+        self.elapsed = (self.elapsed + delta_time).min(self.duration);
+    }
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+// moves a tween stored in a widget towards `target` by one frame and returns its value,
+// widgets keep them in a `Cell` since most of their callbacks only get `&self`
+pub fn step(tween: &Cell<Tween>, target: f32, delta_time: f32) -> f32 {
+    let mut value = tween.get();
+    value.set_target(target);
+    value.update(delta_time);
+    tween.set(value);
+    value.value()
+}
+
+// the tween widgets use to fade their hover outline
+pub fn hover_fade() -> Cell<Tween> {
+    Cell::new(Tween::new(0.0, HOVER_DURATION, Easing::QuadOut))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EASINGS: [Easing; 9] = [
+        Easing::Linear, Easing::QuadIn, Easing::QuadOut, Easing::QuadInOut, Easing::CubicIn,
+        Easing::CubicOut, Easing::CubicInOut, Easing::SineInOut, Easing::BackOut,
+    ];
+
+    #[test]
+    fn eases_from_zero_to_one() {
+        for easing in EASINGS {
+            assert!(easing.apply(0.0).abs() < 1e-6, "{:?} starts at {}", easing, easing.apply(0.0));
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{:?} ends at {}", easing, easing.apply(1.0));
+            // time outside 0..1 is clamped
+            assert_eq!(easing.apply(-1.0), easing.apply(0.0));
+            assert_eq!(easing.apply(2.0), easing.apply(1.0));
+        }
+        // the in-out curves are symmetric around the middle
+        for easing in [Easing::QuadInOut, Easing::CubicInOut, Easing::SineInOut] {
+            assert!((easing.apply(0.5) - 0.5).abs() < 1e-6);
+        }
+        assert!(Easing::BackOut.apply(0.8) > 1.0);
+    }
+
+    #[test]
+    fn tweens_towards_targets() {
+        let mut tween = Tween::new(0.0, 1.0, Easing::Linear);
+        assert!(tween.is_finished());
+        assert_eq!(tween.value(), 0.0);
+
+        tween.set_target(10.0);
+        assert!(!tween.is_finished());
+        tween.update(0.25);
+        assert!((tween.value() - 2.5).abs() < 1e-5);
+
+        // a new target starts from where the value is instead of jumping
+        tween.set_target(0.0);
+        assert!((tween.value() - 2.5).abs() < 1e-5);
+        tween.update(0.5);
+        assert!((tween.value() - 1.25).abs() < 1e-5);
+
+        // and never runs past the end
+        tween.update(5.0);
+        assert!(tween.is_finished());
+        assert_eq!(tween.value(), 0.0);
+
+        tween.snap(3.0);
+        assert!(tween.is_finished());
+        assert_eq!((tween.value(), tween.target()), (3.0, 3.0));
+    }
+
+    #[test]
+    fn jumps_without_duration() {
+        let mut tween = Tween::new(0.0, 0.0, Easing::QuadOut);
+        tween.set_target(1.0);
+        assert_eq!(tween.value(), 1.0);
+    }
+
+    #[test]
+    fn steps_tweens_in_cells() {
+        let tween = Cell::new(Tween::new(0.0, 1.0, Easing::Linear));
+        assert!((step(&tween, 1.0, 0.5) - 0.5).abs() < 1e-5);
+        assert!((step(&tween, 1.0, 0.5) - 1.0).abs() < 1e-5);
+    }
+}
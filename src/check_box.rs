@@ -1,34 +1,48 @@
-use std::cell::RefCell;
+use std::cell::{ Cell, RefCell };
 use std::rc::Rc;
 
-use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, label };
+use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, ContextActions, label, context_menu, primitives, animation, error::MenuError };
 
-use glium::{ Surface, uniform, Frame };
+use glium::Frame;
 
 pub struct CheckBox {
     options: MenuOptions,
     pub rect: Rect,
-    color: Vec4,
+    color: Option<Vec4>,
     toggle: Rc<RefCell<bool>>,
+    default: bool,
     label: Option<label::Label>,
+    hover: Cell<animation::Tween>,
+    // 0 unchecked to 1 checked, how much of the check mark is drawn
+    check: Cell<animation::Tween>,
+    actions: Vec<context_menu::ContextAction>,
 }
 
 impl CheckBox {
     pub fn new(
         options: MenuOptions,
         rect: Rect,
-        color: Vec4,
+        color: Option<Vec4>,
         toggle: Rc<RefCell<bool>>,
         label: Option<label::Label>
     ) -> Self {
+        let default = *toggle.borrow();
+        let check = animation::Tween::new(if default { 1.0 } else { 0.0 }, animation::TOGGLE_DURATION, animation::Easing::CubicOut);
         Self {
             options,
             rect,
             toggle,
+            default,
             color,
             label,
+            hover: animation::hover_fade(),
+            check: Cell::new(check),
+            actions: Vec::new(),
         }
     }
+    pub fn add_context_action(&mut self, action: context_menu::ContextAction) {
+        self.actions.push(action);
+    }
     pub fn do_toggle(&self) {
         let mut value = self.toggle.borrow_mut();
         *value = !*value;
@@ -36,12 +50,9 @@ impl CheckBox {
     fn draw_check(
         &self,
         menu: &mut Menu,
-        frame: &mut Frame
-    ) {
-        let uniforms = uniform! {
-            screen_size: [menu.window_size.0 as f32, menu.window_size.1 as f32]
-        };
-
+        frame: &mut Frame,
+        progress: f32
+    ) -> Result<(), MenuError> {
         let shape = vec![
             Vertex { p: [ self.rect.top_left.p[0] + self.rect.width * 0.20,
                           self.rect.top_left.p[1] + self.rect.height * 0.60 ] },
@@ -51,48 +62,16 @@ impl CheckBox {
                           self.rect.top_left.p[1] + self.rect.height * 0.05 ] },
         ];
 
-        let vertex_buffer = glium::VertexBuffer::new(&menu.display, &shape).unwrap();
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::LineStrip);
-
-        let vertex_shader_src = r#"
-        #version 140
-
-        in vec2 p;
-        uniform vec2 screen_size;
-
-        void main() {
-        vec2 zero_to_one = p / screen_size;
-        vec2 zero_to_two = zero_to_one * 2.0;
-        vec2 clip_space = zero_to_two - 1.0;
-        clip_space.y = -clip_space.y;
-
-        gl_Position = vec4(clip_space, 0.0, 1.0);
-        }
-        "#;
-        let fragment_shader_src = r#"
-        #version 140
-
-        out vec4 color;
-
-        void main() {
-        color = vec4(1.0, 0.0, 0.0, 1.0);
-        }
-        "#;
-
-        let params = glium::DrawParameters {
-            line_width: Some(4.0),
-            .. Default::default()
-        };
+        let mut style = primitives::StrokeStyle::new(menu.theme.border_width);
+        style.join = primitives::LineJoin::Round;
+        style.cap = primitives::LineCap::Round;
 
-        let program = glium::Program::from_source(&menu.display, vertex_shader_src, fragment_shader_src, None).unwrap();
+        // partially drawn and faded while it animates between states
+        let mut color = menu.theme.accent;
+        color.v[3] *= progress;
+        let shape = primitives::trim_polyline(&shape, progress);
 
-        frame.draw(
-            &vertex_buffer,
-            &indices,
-            &program,
-            &uniforms,
-            &params
-        ).unwrap();
+        primitives::draw_polyline(menu, frame, &shape, &style, color)
     }
 }
 
@@ -115,18 +94,8 @@ impl Hovering for CheckBox {
         &self,
         menu: &mut Menu,
         frame: &mut Frame,
-    ) {
-        if self.in_bounds(menu) {
-            let top_left = Vertex { p: [ self.rect.top_left.p[0] - 2.0, self.rect.top_left.p[1] - 2.0] };
-            let mut outline = outline_box::OutlineBox::new(
-                MenuOptions::new(false, false, true, true),
-                Rect::new(top_left, self.rect.width + 4.0, self.rect.height + 4.0),
-                Vec4::new(1.0, 0.0, 0.0, 1.0),
-                4.0,
-                None,
-            );
-            outline.draw(menu, frame);
-        }
+    ) -> Result<(), MenuError> {
+        self.rect.is_hovering_animated(menu, frame, &self.hover)
     }
 }
 
@@ -168,6 +137,20 @@ impl Clicked for CheckBox {
     }
 }
 
+impl ContextActions for CheckBox {
+    fn context_actions(&self) -> Vec<context_menu::ContextAction> {
+        let toggle = Rc::clone(&self.toggle);
+        let default = self.default;
+        let mut actions = vec![
+            context_menu::ContextAction::new("Reset to default", move || {
+                *toggle.borrow_mut() = default;
+            }),
+        ];
+        actions.extend(self.actions.iter().cloned());
+        actions
+    }
+}
+
 impl Options for CheckBox {
     fn get_options(&self) -> MenuOptions {
         self.options
@@ -179,67 +162,22 @@ impl Draw for CheckBox {
         &mut self,
         menu: &mut Menu,
         frame: &mut Frame
-    ) {
-        let uniforms = uniform! {
-            screen_size: [menu.window_size.0 as f32, menu.window_size.1 as f32],
-            color_input: [self.color.v[0], self.color.v[1], self.color.v[2], self.color.v[3]]
-        };
-
-        let shape = vec![
-            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] ] },
-            Vertex { p: [ self.rect.top_left.p[0] + self.rect.width, self.rect.top_left.p[1]] },
-            Vertex { p: [ self.rect.top_left.p[0] + self.rect.width, self.rect.top_left.p[1] + self.rect.height] },
-            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] + self.rect.height] },
-        ];
-
-        let vertex_buffer = glium::VertexBuffer::new(&menu.display, &shape).unwrap();
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleFan);
-
-        let vertex_shader_src = r#"
-        #version 140
-
-        in vec2 p;
-        uniform vec2 screen_size;
-
-        void main() {
-        vec2 zero_to_one = p / screen_size;
-        vec2 zero_to_two = zero_to_one * 2.0;
-        vec2 clip_space = zero_to_two - 1.0;
-        clip_space.y = -clip_space.y;
-
-        gl_Position = vec4(clip_space, 0.0, 1.0);
-        }
-        "#;
-        let fragment_shader_src = r#"
-        #version 140
-
-        uniform vec4 color_input;
-        out vec4 color;
-
-        void main() {
-        color = color_input;
-        }
-        "#;
-
-        let program = glium::Program::from_source(&menu.display, vertex_shader_src, fragment_shader_src, None).unwrap();
-
-        frame.draw(
-            &vertex_buffer,
-            &indices,
-            &program,
-            &uniforms,
-            &Default::default()
-        ).unwrap();
+    ) -> Result<(), MenuError> {
+        let fill = menu.theme.widget_fill(self.color);
+        primitives::fill_rounded_rect_with(menu, frame, &self.rect, [menu.theme.rounding; 4], &fill)?;
 
         // if we have a label we draw it.
         match &self.label {
-            Some(label) => label.draw(&self.rect, menu, frame),
+            Some(label) => label.draw(&self.rect, menu, frame)?,
             None => (),
         };
 
-        if *self.toggle.borrow() {
-            println!("Drawing check");
-            self.draw_check(menu, frame);
+        let target = if *self.toggle.borrow() { 1.0 } else { 0.0 };
+        let progress = animation::step(&self.check, target, menu.delta_time);
+        if progress > 0.0 {
+            self.draw_check(menu, frame, progress)?;
         }
+
+        Ok(())
     }
 }
@@ -0,0 +1,114 @@
+use std::rc::Rc;
+
+use crate::{ Rect, Menu, Vertex, Draw, InBounds, MenuOptions, filled_box, fonts, primitives, error::MenuError };
+
+use glium::Frame;
+
+// height of a single entry in pixels
+const ENTRY_HEIGHT: f32 = 24.0;
+// width of the whole context menu in pixels
+const MENU_WIDTH: f32 = 160.0;
+
+#[derive(Clone)]
+pub struct ContextAction {
+    pub name: String,
+    action: Rc<dyn Fn()>,
+}
+
+impl ContextAction {
+    pub fn new<F>(name: &str, action: F) -> Self
+        where F: Fn() + 'static
+    {
+        Self {
+            name: String::from(name),
+            action: Rc::new(action),
+        }
+    }
+    pub fn run(&self) {
+        (self.action)();
+    }
+}
+
+pub struct ContextMenu {
+    pub rect: Rect,
+    actions: Vec<ContextAction>,
+}
+
+impl ContextMenu {
+    pub fn new(position: (f32, f32), actions: Vec<ContextAction>) -> Self {
+        let height = ENTRY_HEIGHT * actions.len() as f32;
+        Self {
+            rect: Rect::new(Vertex { p: [ position.0, position.1 ] }, MENU_WIDTH, height),
+            actions,
+        }
+    }
+    fn entry_rect(&self, index: usize) -> Rect {
+        Rect::new(
+            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] + ENTRY_HEIGHT * index as f32 ] },
+            self.rect.width,
+            ENTRY_HEIGHT
+        )
+    }
+    // index of the entry under the mouse, if any
+    pub fn entry_at(&self, menu: &Menu) -> Option<usize> {
+        (0..self.actions.len()).find(|i| self.entry_rect(*i).in_bounds(menu))
+    }
+    pub fn run(&self, index: usize) {
+        if let Some(action) = self.actions.get(index) {
+            action.run();
+        }
+    }
+}
+
+impl InBounds for ContextMenu {
+    fn in_bounds(
+        &self,
+        menu: &Menu
+    ) -> bool {
+        self.rect.in_bounds(menu)
+    }
+}
+
+impl Draw for ContextMenu {
+    fn draw(
+        &mut self,
+        menu: &mut Menu,
+        frame: &mut Frame
+    ) -> Result<(), MenuError> {
+        let mut background = filled_box::FilledBox::panel(
+            MenuOptions::new(false, false, true, false),
+            Rect::new(self.rect.top_left, self.rect.width, self.rect.height),
+        );
+        background.draw(menu, frame)?;
+
+        let hovered = self.entry_at(menu);
+        let font = menu.fonts.default_font();
+        let size = menu.theme.small_font_size;
+        let ascent = menu.fonts.get(font).ascent() * size;
+        let color = primitives::faded(menu, menu.theme.text);
+
+        for (i, action) in self.actions.iter().enumerate() {
+            let entry = self.entry_rect(i);
+
+            if hovered == Some(i) {
+                let mut highlight = filled_box::FilledBox::new(
+                    MenuOptions::new(false, false, true, false),
+                    Rect::new(entry.top_left, entry.width, entry.height),
+                    None,
+                    None
+                );
+                highlight.draw(menu, frame)?;
+            }
+
+            // the names stay the same, so the font registry keeps them laid out between frames.
+            // they sit against the left edge and centered vertically, following the menu's offset
+            let baseline = Vertex { p: [
+                entry.top_left.p[0] + menu.theme.padding + menu.offset.0,
+                entry.top_left.p[1] + (entry.height - size) / 2.0 + ascent + menu.offset.1
+            ] };
+            fonts::draw_text(menu, frame, font, &action.name, baseline, size, color)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+
+use crate::rusttype;
+
+#[derive(Debug)]
+pub enum MenuError {
+    /// No window matching this title or search is open.
+    WindowNotFound(String),
+    /// A window handle could not be obtained or is no longer valid.
+    InvalidHandle,
+    /// The event loop could not be created, usually because it was not built on the main thread.
+    EventLoop(winit::error::EventLoopError),
+    /// The overlay window or its OpenGL context could not be created.
+    WindowCreation(String),
+    /// A shader program failed to compile or link.
+    ShaderCompile(glium::ProgramCreationError),
+    /// A vertex or index buffer could not be created.
+    Buffer(glium::vertex::BufferCreationError),
+    /// A texture could not be created.
+    Texture(glium::texture::TextureCreationError),
+    /// A draw call was rejected.
+    Draw(glium::DrawError),
+    /// The finished frame could not be presented.
+    SwapBuffers(glium::SwapBuffersError),
+    /// A font could not be loaded or rasterized.
+    Font(rusttype::Error),
+    /// A font file could not be read.
+    FontFile(std::path::PathBuf, std::io::Error),
+    /// No installed font belongs to this family.
+    FontNotFound(String),
+}
+
+impl fmt::Display for MenuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MenuError::WindowNotFound(search) => write!(f, "no window matching \"{}\" is open", search),
+            MenuError::InvalidHandle => write!(f, "window handle is invalid"),
+            MenuError::EventLoop(error) => write!(f, "event loop failed to build: {}", error),
+            MenuError::WindowCreation(message) => write!(f, "overlay window creation failed: {}", message),
+            MenuError::ShaderCompile(error) => write!(f, "shader failed to compile: {}", error),
+            MenuError::Buffer(error) => write!(f, "buffer creation failed: {:?}", error),
+            MenuError::Texture(error) => write!(f, "texture creation failed: {:?}", error),
+            MenuError::Draw(error) => write!(f, "draw failed: {}", error),
+            MenuError::SwapBuffers(error) => write!(f, "presenting the frame failed: {:?}", error),
+            MenuError::Font(error) => write!(f, "font error: {:?}", error),
+            MenuError::FontFile(path, error) => write!(f, "font file {} could not be read: {}", path.display(), error),
+            MenuError::FontNotFound(family) => write!(f, "no font of the family \"{}\" is installed", family),
+        }
+    }
+}
+
+impl std::error::Error for MenuError {}
+
+impl From<winit::error::EventLoopError> for MenuError {
+    fn from(error: winit::error::EventLoopError) -> Self {
+        MenuError::EventLoop(error)
+    }
+}
+
+impl From<glutin::error::Error> for MenuError {
+    fn from(error: glutin::error::Error) -> Self {
+        MenuError::WindowCreation(error.to_string())
+    }
+}
+
+impl From<glium::ProgramCreationError> for MenuError {
+    fn from(error: glium::ProgramCreationError) -> Self {
+        MenuError::ShaderCompile(error)
+    }
+}
+
+impl From<glium::vertex::BufferCreationError> for MenuError {
+    fn from(error: glium::vertex::BufferCreationError) -> Self {
+        MenuError::Buffer(error)
+    }
+}
+
+impl From<glium::texture::TextureCreationError> for MenuError {
+    fn from(error: glium::texture::TextureCreationError) -> Self {
+        MenuError::Texture(error)
+    }
+}
+
+impl From<glium::DrawError> for MenuError {
+    fn from(error: glium::DrawError) -> Self {
+        MenuError::Draw(error)
+    }
+}
+
+impl From<glium::SwapBuffersError> for MenuError {
+    fn from(error: glium::SwapBuffersError) -> Self {
+        MenuError::SwapBuffers(error)
+    }
+}
+
+impl From<rusttype::Error> for MenuError {
+    fn from(error: rusttype::Error) -> Self {
+        match error {
+            // text that failed to draw failed like any other draw call
+            rusttype::Error::Draw(error) => MenuError::Draw(error),
+            error => MenuError::Font(error),
+        }
+    }
+}
+
+// lets callers that already return `std::io::Error` keep using `?`
+impl From<MenuError> for std::io::Error {
+    fn from(error: MenuError) -> Self {
+        std::io::Error::new(std::io::ErrorKind::Other, error.to_string())
+    }
+}
@@ -0,0 +1,185 @@
+use crate::{ Rect, Vec4 };
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorStop {
+    // position along the gradient, 0..1
+    pub offset: f32,
+    pub color: Vec4,
+}
+
+impl ColorStop {
+    pub fn new(offset: f32, color: Vec4) -> Self {
+        Self {
+            offset,
+            color,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fill {
+    Solid(Vec4),
+    // angle in radians, 0 runs left to right and PI / 2 top to bottom
+    Linear { angle: f32, stops: Vec<ColorStop> },
+    // center and radius are relative to the filled rect, center (0.5, 0.5) and radius 0.5 touch its sides
+    Radial { center: (f32, f32), radius: f32, stops: Vec<ColorStop> },
+}
+
+impl Fill {
+    pub fn linear(angle: f32, stops: &[(f32, Vec4)]) -> Self {
+        Fill::Linear { angle, stops: to_stops(stops) }
+    }
+    pub fn radial(center: (f32, f32), radius: f32, stops: &[(f32, Vec4)]) -> Self {
+        Fill::Radial { center, radius, stops: to_stops(stops) }
+    }
+    // top to bottom, the common case for panels and buttons
+    pub fn vertical(top: Vec4, bottom: Vec4) -> Self {
+        Fill::linear(std::f32::consts::PI / 2.0, &[ (0.0, top), (1.0, bottom) ])
+    }
+    pub fn is_solid(&self) -> bool {
+        matches!(self, Fill::Solid(_))
+    }
+    // color at `t` along the gradient, clamped to the first and last stop
+    pub fn sample(&self, t: f32) -> Vec4 {
+        let stops = match self {
+            Fill::Solid(color) => return *color,
+            Fill::Linear { stops, .. } | Fill::Radial { stops, .. } => stops,
+        };
+        sample_stops(stops, t)
+    }
+    // start and end of the gradient line in pixels, the whole rect lies between them
+    pub fn linear_points(angle: f32, rect: &Rect) -> ([f32; 2], [f32; 2]) {
+        let center = [ rect.top_left.p[0] + rect.width / 2.0, rect.top_left.p[1] + rect.height / 2.0 ];
+        let direction = [ angle.cos(), angle.sin() ];
+        let half_length = (rect.width / 2.0 * direction[0]).abs() + (rect.height / 2.0 * direction[1]).abs();
+        (
+            [ center[0] - direction[0] * half_length, center[1] - direction[1] * half_length ],
+            [ center[0] + direction[0] * half_length, center[1] + direction[1] * half_length ],
+        )
+    }
+    // radial center and radius in pixels
+    pub fn radial_points(center: (f32, f32), radius: f32, rect: &Rect) -> ([f32; 2], f32) {
+        (
+            [ rect.top_left.p[0] + rect.width * center.0, rect.top_left.p[1] + rect.height * center.1 ],
+            radius * rect.width.max(rect.height),
+        )
+    }
+    // identifies the colors of a gradient, the same key means the same `lookup_table`
+    pub(crate) fn stops_key(&self) -> Vec<u32> {
+        let stops = match self {
+            Fill::Solid(color) => return color.v.iter().map(|c| c.to_bits()).collect(),
+            Fill::Linear { stops, .. } | Fill::Radial { stops, .. } => stops,
+        };
+        stops.iter()
+            .flat_map(|stop| std::iter::once(stop.offset).chain(stop.color.v))
+            .map(f32::to_bits)
+            .collect()
+    }
+    // the gradient baked into `size` rgba8 texels for the gradient shader to look up
+    pub fn lookup_table(&self, size: usize) -> Vec<u8> {
+        let size = size.max(2);
+        let mut table = Vec::with_capacity(size * 4);
+        for i in 0..size {
+            let color = self.sample(i as f32 / (size - 1) as f32);
+            table.extend(color.v.iter().map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8));
+        }
+        table
+    }
+}
+
+impl From<Vec4> for Fill {
+    fn from(color: Vec4) -> Self {
+        Fill::Solid(color)
+    }
+}
+
+fn to_stops(stops: &[(f32, Vec4)]) -> Vec<ColorStop> {
+    let mut stops: Vec<ColorStop> = stops.iter().map(|(offset, color)| ColorStop::new(*offset, *color)).collect();
+    stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+    stops
+}
+
+fn sample_stops(stops: &[ColorStop], t: f32) -> Vec4 {
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Vec4::default(),
+    };
+    if t <= first.offset {
+        return first.color
+    }
+    if t >= last.offset {
+        return last.color
+    }
+    for window in stops.windows(2) {
+        let (a, b) = (window[0], window[1]);
+        if t >= a.offset && t <= b.offset {
+            let span = b.offset - a.offset;
+            let f = if span > 0.0 { (t - a.offset) / span } else { 0.0 };
+            let mut color = a.color;
+            for c in 0..4 {
+                color.v[c] = a.color.v[c] + (b.color.v[c] - a.color.v[c]) * f;
+            }
+            return color
+        }
+    }
+    last.color
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vertex;
+
+    fn black() -> Vec4 {
+        Vec4::new(0.0, 0.0, 0.0, 1.0)
+    }
+
+    fn white() -> Vec4 {
+        Vec4::new(1.0, 1.0, 1.0, 1.0)
+    }
+
+    #[test]
+    fn samples_stops() {
+        let fill = Fill::linear(0.0, &[ (0.75, white()), (0.25, black()) ]);
+        // stops are sorted, and the ends hold their colors past the first and last stop
+        assert_eq!(fill.sample(0.0), black());
+        assert_eq!(fill.sample(0.25), black());
+        assert_eq!(fill.sample(0.75), white());
+        assert_eq!(fill.sample(1.0), white());
+        assert_eq!(fill.sample(0.5), Vec4::new(0.5, 0.5, 0.5, 1.0));
+
+        assert_eq!(Fill::Solid(white()).sample(0.3), white());
+        assert_eq!(sample_stops(&[], 0.5), Vec4::default());
+        // stops at the same offset switch colors there instead of dividing by zero
+        let hard = Fill::linear(0.0, &[ (0.0, black()), (0.5, black()), (0.5, white()), (1.0, white()) ]);
+        assert_eq!(hard.sample(0.25), black());
+        assert_eq!(hard.sample(0.75), white());
+    }
+
+    #[test]
+    fn bakes_lookup_tables() {
+        let table = Fill::vertical(black(), white()).lookup_table(16);
+        assert_eq!(table.len(), 16 * 4);
+        assert_eq!(&table[.. 4], &[ 0, 0, 0, 255 ]);
+        assert_eq!(&table[table.len() - 4 ..], &[ 255, 255, 255, 255 ]);
+        // never fewer than the two ends
+        assert_eq!(Fill::vertical(black(), white()).lookup_table(0).len(), 2 * 4);
+    }
+
+    #[test]
+    fn keys_stops() {
+        let fill = Fill::vertical(black(), white());
+        assert_eq!(fill.stops_key(), Fill::linear(0.0, &[ (0.0, black()), (1.0, white()) ]).stops_key());
+        assert_ne!(fill.stops_key(), Fill::vertical(white(), black()).stops_key());
+    }
+
+    #[test]
+    fn places_gradients_on_rects() {
+        let rect = Rect::new(Vertex { p: [ 10.0, 20.0 ] }, 100.0, 50.0);
+        let (start, end) = Fill::linear_points(0.0, &rect);
+        assert_eq!((start, end), ([ 10.0, 45.0 ], [ 110.0, 45.0 ]));
+
+        let (center, radius) = Fill::radial_points((0.5, 0.5), 0.5, &rect);
+        assert_eq!((center, radius), ([ 60.0, 45.0 ], 50.0));
+    }
+}
@@ -1,24 +1,72 @@
-use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, label };
+use std::cell::Cell;
 
-use glium::{ Surface, uniform, Frame };
+use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, ContextActions, label, context_menu, primitives, fill, animation, error::MenuError };
+
+use glium::Frame;
 
 #[derive(Default)]
 pub struct FilledBox {
     options: MenuOptions,
     pub rect: Rect,
-    color: Vec4,
+    color: Option<Vec4>,
+    fill: Option<fill::Fill>,
+    // panels take the theme's panel color instead of the widget color
+    panel: bool,
+    rounding: Option<[f32; 4]>,
+    shadow: Option<primitives::Shadow>,
     label: Option<label::Label>,
+    hover: Cell<animation::Tween>,
+    actions: Vec<context_menu::ContextAction>,
 }
 
 impl FilledBox {
-    pub fn new(options: MenuOptions, rect: Rect, color: Vec4, label: Option<label::Label>) -> Self {
+    pub fn new(options: MenuOptions, rect: Rect, color: Option<Vec4>, label: Option<label::Label>) -> Self {
         Self {
             options,
             rect,
             color,
+            fill: None,
+            panel: false,
+            rounding: None,
+            shadow: None,
             label,
+            hover: animation::hover_fade(),
+            actions: Vec::new(),
+        }
+    }
+    pub fn panel(options: MenuOptions, rect: Rect) -> Self {
+        Self {
+            options,
+            rect,
+            panel: true,
+            hover: animation::hover_fade(),
+            .. Default::default()
         }
     }
+    pub fn set_color(&mut self, color: Option<Vec4>) {
+        self.color = color;
+    }
+    // per corner radius, top left, top right, bottom right, bottom left
+    pub fn set_rounding(&mut self, rounding: Option<[f32; 4]>) {
+        self.rounding = rounding;
+    }
+    pub fn set_shadow(&mut self, shadow: Option<primitives::Shadow>) {
+        self.shadow = shadow;
+    }
+    // a solid color or gradient, takes priority over the color
+    pub fn set_fill(&mut self, fill: Option<fill::Fill>) {
+        self.fill = fill;
+    }
+    fn fill(&self, menu: &Menu) -> fill::Fill {
+        match &self.fill {
+            Some(fill) => fill.clone(),
+            None if self.panel => menu.theme.panel_fill(self.color),
+            None => menu.theme.widget_fill(self.color),
+        }
+    }
+    pub fn add_context_action(&mut self, action: context_menu::ContextAction) {
+        self.actions.push(action);
+    }
 }
 
 impl InBounds for FilledBox {
@@ -40,18 +88,8 @@ impl Hovering for FilledBox {
         &self,
         menu: &mut Menu,
         frame: &mut Frame,
-    ) {
-        if self.in_bounds(menu) {
-            let top_left = Vertex { p: [ self.rect.top_left.p[0] - 2.0, self.rect.top_left.p[1] - 2.0] };
-            let mut outline = outline_box::OutlineBox::new(
-                MenuOptions::new(false, false, false, true),
-                Rect::new(top_left, self.rect.width + 4.0, self.rect.height + 4.0),
-                Vec4::new(1.0, 0.0, 0.0, 1.0),
-                4.0,
-                None,
-            );
-            outline.draw(menu, frame);
-        }
+    ) -> Result<(), MenuError> {
+        self.rect.is_hovering_animated(menu, frame, &self.hover)
     }
 }
 
@@ -92,6 +130,12 @@ impl Clicked for FilledBox {
     }
 }
 
+impl ContextActions for FilledBox {
+    fn context_actions(&self) -> Vec<context_menu::ContextAction> {
+        self.actions.clone()
+    }
+}
+
 impl Options for FilledBox {
     fn get_options(&self) -> MenuOptions {
         self.options
@@ -103,61 +147,26 @@ impl Draw for FilledBox {
         &mut self,
         menu: &mut Menu,
         frame: &mut Frame
-    ) {
-        let uniforms = uniform! {
-            screen_size: [menu.window_size.0 as f32, menu.window_size.1 as f32],
-            color_input: [self.color.v[0], self.color.v[1], self.color.v[2], self.color.v[3]]
+    ) -> Result<(), MenuError> {
+        let fill = self.fill(menu);
+        let radii = self.rounding.unwrap_or([menu.theme.rounding; 4]);
+
+        let shadow = match self.shadow {
+            Some(shadow) => Some(shadow),
+            None if self.panel => menu.theme.panel_shadow(),
+            None => None,
         };
-
-        let shape = vec![
-            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] ] },
-            Vertex { p: [ self.rect.top_left.p[0] + self.rect.width, self.rect.top_left.p[1]] },
-            Vertex { p: [ self.rect.top_left.p[0] + self.rect.width, self.rect.top_left.p[1] + self.rect.height] },
-            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] + self.rect.height] },
-        ];
-
-        let vertex_buffer = glium::VertexBuffer::new(&menu.display, &shape).unwrap();
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleFan);
-
-        let vertex_shader_src = r#"
-        #version 140
-
-        in vec2 p;
-        uniform vec2 screen_size;
-
-        void main() {
-        vec2 zero_to_one = p / screen_size;
-        vec2 zero_to_two = zero_to_one * 2.0;
-        vec2 clip_space = zero_to_two - 1.0;
-        clip_space.y = -clip_space.y;
-
-        gl_Position = vec4(clip_space, 0.0, 1.0);
+        if let Some(shadow) = shadow {
+            primitives::draw_drop_shadow(menu, frame, &self.rect, radii[0], &shadow)?;
         }
-        "#;
-        let fragment_shader_src = r#"
-        #version 140
 
-        uniform vec4 color_input;
-        out vec4 color;
-
-        void main() {
-        color = color_input;
-        }
-        "#;
-
-        let program = glium::Program::from_source(&menu.display, vertex_shader_src, fragment_shader_src, None).unwrap();
-
-        frame.draw(
-            &vertex_buffer,
-            &indices,
-            &program,
-            &uniforms,
-            &Default::default()
-        ).unwrap();
+        primitives::fill_rounded_rect_with(menu, frame, &self.rect, radii, &fill)?;
 
         match &self.label {
-            Some(label) => label.draw(&self.rect, menu, frame),
+            Some(label) => label.draw(&self.rect, menu, frame)?,
             None => (),
         };
+
+        Ok(())
     }
 }
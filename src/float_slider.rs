@@ -1,56 +1,70 @@
-use std::cell::RefCell;
+use std::cell::{ Cell, RefCell };
 use std::rc::Rc;
 
-use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, label };
+use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, ContextActions, label, context_menu, windows_api, primitives, animation, error::MenuError };
 
-use glium::{ Surface, uniform, Frame };
+use glium::Frame;
 
 #[derive(Default)]
 pub struct FloatSlider {
     options: MenuOptions,
     pub rect: Rect,
-    color: Vec4,
+    color: Option<Vec4>,
     slider: Rc<RefCell<f32>>,
+    default: f32,
     min: f32,
     max: f32,
     holding: Rc<RefCell<bool>>,
-    label: Option<label::Label>
+    label: Option<label::Label>,
+    hover: Cell<animation::Tween>,
+    // where the knob is drawn, eases towards the slider value
+    knob: Cell<animation::Tween>,
+    actions: Vec<context_menu::ContextAction>,
 }
 
 impl FloatSlider {
     pub fn new(
         options: MenuOptions,
         rect: Rect,
-        color: Vec4,
+        color: Option<Vec4>,
         slider: Rc<RefCell<f32>>,
         min:f32,
         max: f32,
         label: Option<label::Label>
     ) -> Self {
+        let default = *slider.borrow();
+        let knob = animation::Tween::new(default / max, animation::SLIDER_DURATION, animation::Easing::QuadOut);
         Self {
             options,
             rect,
             color,
             slider,
+            default,
             min,
             max,
             holding: Rc::new(RefCell::new(false)),
             label,
+            hover: animation::hover_fade(),
+            knob: Cell::new(knob),
+            actions: Vec::new(),
         }
     }
-    fn draw_slider(&self, menu: &mut Menu, frame: &mut Frame) {
-        let uniforms = uniform! {
-            screen_size: [menu.window_size.0 as f32, menu.window_size.1 as f32],
-            color_input: [self.color.v[0], self.color.v[1], self.color.v[2], self.color.v[3]]
-        };
-
+    pub fn add_context_action(&mut self, action: context_menu::ContextAction) {
+        self.actions.push(action);
+    }
+    fn draw_slider(&self, menu: &mut Menu, frame: &mut Frame) -> Result<(), MenuError> {
         let x = self.rect.top_left.p[0] + self.rect.width * (*self.slider.borrow() / self.max);
         let slider_rect = Rect::new(
             Vertex { p: [ x, self.rect.top_left.p[1] - self.rect.height ] },
             self.rect.width * 0.10, self.rect.height * 3.0
         );
+        // dragging uses the real value, only the drawn knob is smoothed
+        let smoothed = animation::step(&self.knob, *self.slider.borrow() / self.max, menu.delta_time);
+        let mut knob_rect = slider_rect;
+        knob_rect.top_left.p[0] = self.rect.top_left.p[0] + self.rect.width * smoothed;
 
-        slider_rect.is_hovering(menu, frame);
+        let amount = if slider_rect.in_bounds(menu) { 1.0 } else { 0.0 };
+        knob_rect.draw_hover(menu, frame, amount)?;
         if menu.mouse_pos.0 != menu.cached_mouse_pos.0 || menu.mouse_pos.1 != menu.cached_mouse_pos.1 {
             let new_x = slider_rect.top_left.p[0] + menu.mouse_pos.0 - menu.cached_mouse_pos.0;
 
@@ -68,52 +82,8 @@ impl FloatSlider {
             *self.holding.borrow_mut() = false;
         }
 
-        let shape = vec![
-            Vertex { p: [ slider_rect.top_left.p[0], slider_rect.top_left.p[1] ] },
-            Vertex { p: [ slider_rect.top_left.p[0] + slider_rect.width, slider_rect.top_left.p[1]] },
-            Vertex { p: [ slider_rect.top_left.p[0] + slider_rect.width,
-                          slider_rect.top_left.p[1] + slider_rect.height] },
-            Vertex { p: [ slider_rect.top_left.p[0], slider_rect.top_left.p[1] + slider_rect.height] },
-        ];
-
-        let vertex_buffer = glium::VertexBuffer::new(&menu.display, &shape).unwrap();
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleFan);
-
-        let vertex_shader_src = r#"
-        #version 140
-
-        in vec2 p;
-        uniform vec2 screen_size;
-
-        void main() {
-        vec2 zero_to_one = p / screen_size;
-        vec2 zero_to_two = zero_to_one * 2.0;
-        vec2 clip_space = zero_to_two - 1.0;
-        clip_space.y = -clip_space.y;
-
-        gl_Position = vec4(clip_space, 0.0, 1.0);
-        }
-        "#;
-        let fragment_shader_src = r#"
-        #version 140
-
-        uniform vec4 color_input;
-        out vec4 color;
-
-        void main() {
-        color = color_input;
-        }
-        "#;
-
-        let program = glium::Program::from_source(&menu.display, vertex_shader_src, fragment_shader_src, None).unwrap();
-
-        frame.draw(
-            &vertex_buffer,
-            &indices,
-            &program,
-            &uniforms,
-            &Default::default()
-        ).unwrap();
+        let color = if *self.holding.borrow() { menu.theme.active } else { menu.theme.accent };
+        primitives::fill_rounded_rect(menu, frame, &knob_rect, [menu.theme.rounding; 4], color)
     }
 }
 
@@ -136,18 +106,8 @@ impl Hovering for FloatSlider {
         &self,
         menu: &mut Menu,
         frame: &mut Frame,
-    ) {
-        if self.in_bounds(menu) {
-            let top_left = Vertex { p: [ self.rect.top_left.p[0] - 2.0, self.rect.top_left.p[1] - 2.0] };
-            let mut outline = outline_box::OutlineBox::new(
-                MenuOptions::new(false, false, false, true),
-                Rect::new(top_left, self.rect.width + 4.0, self.rect.height + 4.0),
-                Vec4::new(1.0, 0.0, 0.0, 1.0),
-                4.0,
-                None
-            );
-            outline.draw(menu, frame);
-        }
+    ) -> Result<(), MenuError> {
+        self.rect.is_hovering_animated(menu, frame, &self.hover)
     }
 }
 
@@ -193,6 +153,34 @@ impl Clicked for FloatSlider {
     }
 }
 
+impl ContextActions for FloatSlider {
+    fn context_actions(&self) -> Vec<context_menu::ContextAction> {
+        let reset = Rc::clone(&self.slider);
+        let default = self.default;
+        let to_min = Rc::clone(&self.slider);
+        let min = self.min;
+        let to_max = Rc::clone(&self.slider);
+        let max = self.max;
+        let copy = Rc::clone(&self.slider);
+        let mut actions = vec![
+            context_menu::ContextAction::new("Reset to default", move || {
+                *reset.borrow_mut() = default;
+            }),
+            context_menu::ContextAction::new("Set to min", move || {
+                *to_min.borrow_mut() = min;
+            }),
+            context_menu::ContextAction::new("Set to max", move || {
+                *to_max.borrow_mut() = max;
+            }),
+            context_menu::ContextAction::new("Copy value", move || {
+                windows_api::copy_to_clipboard(&copy.borrow().to_string());
+            }),
+        ];
+        actions.extend(self.actions.iter().cloned());
+        actions
+    }
+}
+
 impl Options for FloatSlider {
     fn get_options(&self) -> MenuOptions {
         self.options
@@ -204,66 +192,18 @@ impl Draw for FloatSlider {
         &mut self,
         menu: &mut Menu,
         frame: &mut Frame
-    ) {
-        let uniforms = uniform! {
-            screen_size: [menu.window_size.0 as f32, menu.window_size.1 as f32],
-            color_input: [self.color.v[0], self.color.v[1], self.color.v[2], self.color.v[3]]
-        };
-
-        let shape = vec![
-            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] ] },
-            Vertex { p: [ self.rect.top_left.p[0] + self.rect.width, self.rect.top_left.p[1]] },
-            Vertex { p: [ self.rect.top_left.p[0] + self.rect.width, self.rect.top_left.p[1] + self.rect.height] },
-            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] + self.rect.height] },
-        ];
-
-        let vertex_buffer = glium::VertexBuffer::new(&menu.display, &shape).unwrap();
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::TriangleFan);
-
-        let vertex_shader_src = r#"
-        #version 140
-
-        in vec2 p;
-        uniform vec2 screen_size;
-
-        void main() {
-        vec2 zero_to_one = p / screen_size;
-        vec2 zero_to_two = zero_to_one * 2.0;
-        vec2 clip_space = zero_to_two - 1.0;
-        clip_space.y = -clip_space.y;
-
-        gl_Position = vec4(clip_space, 0.0, 1.0);
-        }
-        "#;
-        let fragment_shader_src = r#"
-        #version 140
-
-        uniform vec4 color_input;
-        out vec4 color;
-
-        void main() {
-        color = color_input;
-        }
-        "#;
-
-        let program = glium::Program::from_source(&menu.display, vertex_shader_src, fragment_shader_src, None).unwrap();
-
-        frame.draw(
-            &vertex_buffer,
-            &indices,
-            &program,
-            &uniforms,
-            &Default::default()
-        ).unwrap();
+    ) -> Result<(), MenuError> {
+        let fill = menu.theme.widget_fill(self.color);
+        primitives::fill_rounded_rect_with(menu, frame, &self.rect, [menu.theme.rounding; 4], &fill)?;
 
         match &mut self.label {
             Some(label) => {
-                label.text = format!("{}: {}", label.original_text, *self.slider.borrow());
-                label.draw(&self.rect, menu, frame);
+                label.set_value(*self.slider.borrow());
+                label.draw(&self.rect, menu, frame)?;
             },
             None => (),
         };
 
-        self.draw_slider(menu, frame);
+        self.draw_slider(menu, frame)
     }
 }
@@ -0,0 +1,373 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+use glium::Frame;
+use glium::backend::glutin::Display;
+
+use glutin::surface::WindowSurface;
+
+use crate::{ Menu, Rect, Vertex, Vec4, primitives, rusttype, system_fonts, text_block, text_style, error::MenuError };
+
+// how many frames text drawn with `draw_text` stays cached without being drawn again
+const TRANSIENT_TEXT_FRAMES: u64 = 60;
+
+// a font loaded into a `FontRegistry`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FontId(usize);
+
+// size of some text in pixels, see `FontRegistry::measure_text`
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextMetrics {
+    // of the widest line
+    pub width: f32,
+    // from the top of the first line to the bottom of the last
+    pub height: f32,
+    // how far the font reaches above and below a baseline
+    pub ascent: f32,
+    pub descent: f32,
+}
+
+struct FontEntry {
+    name: String,
+    // shared with the text displays built from it
+    texture: Rc<rusttype::FontTexture>,
+    // tried in order for characters this font doesn't have
+    fallbacks: Vec<FontId>,
+}
+
+// every font a menu can draw text with, the font it was created with is the default
+pub struct FontRegistry {
+    fonts: Vec<FontEntry>,
+    // tried after a font's own fallbacks
+    fallbacks: Vec<FontId>,
+    // text drawn with `draw_text`, reused while the same text keeps being drawn
+    transient: RefCell<HashMap<(FontId, String), TransientText>>,
+    frame: u64,
+    // whether the loaders turn on `rusttype::FontTexture::set_shaping`
+    #[cfg(feature = "shaping")]
+    shaping: bool,
+}
+
+struct TransientText {
+    text: Rc<CachedText>,
+    last_used: u64,
+}
+
+impl FontRegistry {
+    pub fn new(default: rusttype::FontTexture) -> Self {
+        Self {
+            fonts: vec![ FontEntry { name: String::from("default"), texture: Rc::new(default), fallbacks: Vec::new() } ],
+            fallbacks: Vec::new(),
+            transient: RefCell::new(HashMap::new()),
+            frame: 0,
+            #[cfg(feature = "shaping")]
+            shaping: false,
+        }
+    }
+    pub fn default_font(&self) -> FontId {
+        FontId(0)
+    }
+    pub fn add(&mut self, name: &str, texture: rusttype::FontTexture) -> FontId {
+        self.fonts.push(FontEntry { name: name.to_string(), texture: Rc::new(texture), fallbacks: Vec::new() });
+        FontId(self.fonts.len() - 1)
+    }
+    // fonts loaded after this lay their text out with HarfBuzz, the registry shares them with
+    // text displays so they can't be switched over once loaded
+    #[cfg(feature = "shaping")]
+    pub fn set_shaping(&mut self, shaping: bool) {
+        self.shaping = shaping;
+    }
+    // rasterizes glyphs as they're first drawn, so every character the font has works
+    pub fn load_bytes(&mut self, display: &Display<WindowSurface>, name: &str, bytes: &[u8], font_size: u32) -> Result<FontId, MenuError> {
+        let texture = rusttype::FontTexture::dynamic(display, bytes, font_size)?;
+        Ok(self.add_loaded(name, texture))
+    }
+    // an installed font, see `system_fonts::FontQuery::find`
+    pub fn load_system(&mut self, display: &Display<WindowSurface>, name: &str, query: &system_fonts::FontQuery, font_size: u32) -> Result<FontId, MenuError> {
+        self.load_bytes(display, name, &query.load()?, font_size)
+    }
+    pub fn load_file<P: AsRef<Path>>(&mut self, display: &Display<WindowSurface>, name: &str, path: P, font_size: u32) -> Result<FontId, MenuError> {
+        self.load_bytes(display, name, &read_font(path.as_ref())?, font_size)
+    }
+    // stays sharp at any size and can be drawn with `rusttype::TextEffects`, a `font_size`
+    // around 32 is plenty
+    pub fn load_sdf_bytes(&mut self, display: &Display<WindowSurface>, name: &str, bytes: &[u8], font_size: u32) -> Result<FontId, MenuError> {
+        let texture = rusttype::FontTexture::sdf(display, bytes, font_size)?;
+        Ok(self.add_loaded(name, texture))
+    }
+    fn add_loaded(&mut self, name: &str, texture: rusttype::FontTexture) -> FontId {
+        #[cfg(feature = "shaping")]
+        let texture = {
+            let mut texture = texture;
+            texture.set_shaping(self.shaping);
+            texture
+        };
+        self.add(name, texture)
+    }
+    pub fn load_sdf_file<P: AsRef<Path>>(&mut self, display: &Display<WindowSurface>, name: &str, path: P, font_size: u32) -> Result<FontId, MenuError> {
+        self.load_sdf_bytes(display, name, &read_font(path.as_ref())?, font_size)
+    }
+    pub fn by_name(&self, name: &str) -> Option<FontId> {
+        self.fonts.iter().position(|entry| entry.name == name).map(FontId)
+    }
+    // unknown ids get the default font
+    pub fn get(&self, font: FontId) -> &rusttype::FontTexture {
+        &self.entry(font).texture
+    }
+    pub fn name(&self, font: FontId) -> &str {
+        &self.entry(font).name
+    }
+    // fonts tried for characters `font` doesn't have, before the shared chain
+    pub fn set_fallbacks(&mut self, font: FontId, fallbacks: Vec<FontId>) {
+        if let Some(entry) = self.fonts.get_mut(font.0) {
+            entry.fallbacks = fallbacks;
+        }
+    }
+    // fonts tried for characters missing from any font, e.g. an icon or cjk font
+    pub fn set_default_fallbacks(&mut self, fallbacks: Vec<FontId>) {
+        self.fallbacks = fallbacks;
+    }
+    // drops transient text that wasn't drawn for a while, called once per frame by the menu
+    pub(crate) fn end_frame(&mut self) {
+        self.frame += 1;
+        let frame = self.frame;
+        self.transient.get_mut().retain(|_, text| text.last_used + TRANSIENT_TEXT_FRAMES >= frame);
+    }
+    fn transient(&self, system: &rusttype::TextSystem, font: FontId, text: &str) -> Result<Rc<CachedText>, MenuError> {
+        let key = (font, text.to_string());
+        if let Some(entry) = self.transient.borrow_mut().get_mut(&key) {
+            entry.last_used = self.frame;
+            return Ok(Rc::clone(&entry.text))
+        }
+
+        // laid out before borrowing the cache again, rasterizing may take a while
+        let cached = Rc::new(CachedText::new(self, system, font, text)?);
+        self.transient.borrow_mut().insert(key, TransientText {
+            text: Rc::clone(&cached),
+            last_used: self.frame,
+        });
+        Ok(cached)
+    }
+    fn entry(&self, font: FontId) -> &FontEntry {
+        self.fonts.get(font.0).unwrap_or(&self.fonts[0])
+    }
+    // the first font in `font`'s chain that has `character`, `font` itself when none does
+    pub fn resolve(&self, font: FontId, character: char) -> FontId {
+        let font = if font.0 < self.fonts.len() { font } else { self.default_font() };
+        std::iter::once(font)
+            .chain(self.entry(font).fallbacks.iter().copied())
+            .chain(self.fallbacks.iter().copied())
+            .find(|candidate| candidate.0 < self.fonts.len() && self.get(*candidate).has_glyph(character))
+            .unwrap_or(font)
+    }
+    // `text` split into runs of characters that are drawn with the same font
+    pub fn runs(&self, font: FontId, text: &str) -> Vec<(FontId, String)> {
+        let mut runs: Vec<(FontId, String)> = Vec::new();
+        for character in text.chars() {
+            let resolved = self.resolve(font, character);
+            match runs.last_mut() {
+                Some((run_font, run)) if *run_font == resolved => run.push(character),
+                _ => runs.push((resolved, character.to_string())),
+            }
+        }
+        runs
+    }
+    // width in EMs, fallbacks included
+    pub fn text_width(&self, font: FontId, text: &str) -> f32 {
+        self.runs(font, text)
+            .iter()
+            .map(|(run_font, run)| self.get(*run_font).text_width(run))
+            .sum()
+    }
+    // how big `text` is drawn at `px_size`, the height of an em in pixels. newlines start lines
+    // `text_block::DEFAULT_LINE_SPACING` apart like labels do. widths come from the font
+    // metrics, no glyph is rasterized or drawn
+    pub fn measure_text(&self, font: FontId, text: &str, px_size: f32) -> TextMetrics {
+        let texture = self.get(font);
+        let lines = text.split('\n').collect::<Vec<_>>();
+        TextMetrics {
+            width: lines.iter().map(|line| self.text_width(font, line) * px_size).fold(0.0, f32::max),
+            height: px_size * text_block::DEFAULT_LINE_SPACING * (lines.len() - 1) as f32 + px_size,
+            ascent: texture.ascent() * px_size,
+            descent: texture.descent() * px_size,
+        }
+    }
+}
+
+// one line of text laid out into vertex buffers, drawn again without redoing any of it
+pub struct CachedText {
+    font: FontId,
+    text: String,
+    // a display per font the fallbacks split the text into, and where it starts in EMs
+    runs: RefCell<Vec<(f32, rusttype::TextDisplay<Rc<rusttype::FontTexture>>)>>,
+    width: f32,
+}
+
+impl CachedText {
+    pub fn new(fonts: &FontRegistry, system: &rusttype::TextSystem, font: FontId, text: &str) -> Result<Self, MenuError> {
+        let mut runs = Vec::new();
+        let mut x = 0.0;
+        for (run_font, run) in fonts.runs(font, text) {
+            let display = rusttype::TextDisplay::new(system, Rc::clone(&fonts.entry(run_font).texture), &run)?;
+            let width = display.get_width();
+            runs.push((x, display));
+            x += width;
+        }
+        Ok(Self {
+            font,
+            text: text.to_string(),
+            runs: RefCell::new(runs),
+            width: x,
+        })
+    }
+    pub fn matches(&self, font: FontId, text: &str) -> bool {
+        self.font == font && self.text == text
+    }
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+    // in EMs
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+    // draws the text with its baseline starting at `baseline`, `size` is the height of an em in pixels
+    pub fn draw(
+        &self,
+        menu: &Menu,
+        frame: &mut Frame,
+        baseline: Vertex,
+        size: f32,
+        color: Vec4
+    ) -> Result<(), MenuError> {
+        self.draw_with_effects(menu, frame, baseline, size, color, &rusttype::TextEffects::default())
+    }
+    // `effects` only show for runs in fonts loaded with `FontRegistry::load_sdf_bytes`
+    pub fn draw_with_effects(
+        &self,
+        menu: &Menu,
+        frame: &mut Frame,
+        baseline: Vertex,
+        size: f32,
+        color: Vec4,
+        effects: &rusttype::TextEffects
+    ) -> Result<(), MenuError> {
+        for (x, display) in self.runs.borrow_mut().iter_mut() {
+            // a dynamic font moved its glyphs since this was laid out
+            if display.is_stale() {
+                display.refresh()?;
+            }
+            let start = Vertex { p: [ baseline.p[0] + *x * size, baseline.p[1] ] };
+            draw_run(menu, frame, display, start, size, color, effects)?;
+        }
+        Ok(())
+    }
+    // draws the text with the outline and shadow of `style`, colors are faded with the menu.
+    // the plate is left to the caller, it goes behind every line of a block
+    pub fn draw_styled(
+        &self,
+        menu: &Menu,
+        frame: &mut Frame,
+        baseline: Vertex,
+        size: f32,
+        style: &text_style::TextStyle
+    ) -> Result<(), MenuError> {
+        let color = primitives::faded(menu, style.color.unwrap_or(menu.theme.text));
+        let effects = style.effects(menu, size);
+        let plain = rusttype::TextEffects::default();
+
+        for (x, display) in self.runs.borrow_mut().iter_mut() {
+            if display.is_stale() {
+                display.refresh()?;
+            }
+            let start = Vertex { p: [ baseline.p[0] + *x * size, baseline.p[1] ] };
+            if display.get_texture().is_sdf() {
+                draw_run(menu, frame, display, start, size, color, &effects)?;
+                continue
+            }
+
+            // without a distance field the effects are copies of the text, drawn first
+            let moved = |offset: (f32, f32)| Vertex { p: [ start.p[0] + offset.0, start.p[1] + offset.1 ] };
+            if let Some(shadow) = &style.shadow {
+                draw_run(menu, frame, display, moved(shadow.offset), size, primitives::faded(menu, shadow.color), &plain)?;
+            }
+            if let Some(outline) = &style.outline {
+                let outline_color = primitives::faded(menu, outline.color);
+                for (dx, dy) in text_style::OUTLINE_DIRECTIONS {
+                    draw_run(menu, frame, display, moved((dx * outline.width, dy * outline.width)), size, outline_color, &plain)?;
+                }
+            }
+            draw_run(menu, frame, display, start, size, color, &plain)?;
+        }
+        Ok(())
+    }
+}
+
+// one run of a `CachedText` with its baseline starting at `start`
+fn draw_run(
+    menu: &Menu,
+    frame: &mut Frame,
+    display: &rusttype::TextDisplay<Rc<rusttype::FontTexture>>,
+    start: Vertex,
+    size: f32,
+    color: Vec4,
+    effects: &rusttype::TextEffects
+) -> Result<(), MenuError> {
+    let sx = size / (menu.window_size.0 / 2.0);
+    let sy = size / (menu.window_size.1 / 2.0);
+    let x_ndc = (start.p[0] / menu.window_size.0) * 2.0 - 1.0;
+    let y_ndc = -((start.p[1] / menu.window_size.1) * 2.0 - 1.0);
+
+    let matrix: [[f32; 4]; 4] = cgmath::Matrix4::new(
+        sx,  0.0, 0.0, 0.0,
+        0.0, sy,  0.0, 0.0,
+        0.0, 0.0, 1.0, 0.0,
+        x_ndc, y_ndc, 0.0, 1.0,
+    ).into();
+
+    rusttype::draw_with_effects(display, &menu.system, frame, matrix, color.v.into(), effects)?;
+    menu.stats.count_draw_call();
+    Ok(())
+}
+
+// draws `text` with its baseline starting at `baseline`, `size` is the height of an em in pixels.
+// the buffers are kept for as long as the same text is drawn every frame
+pub fn draw_text(
+    menu: &Menu,
+    frame: &mut Frame,
+    font: FontId,
+    text: &str,
+    baseline: Vertex,
+    size: f32,
+    color: Vec4
+) -> Result<(), MenuError> {
+    menu.fonts.transient(&menu.system, font, text)?.draw(menu, frame, baseline, size, color)
+}
+
+// like `draw_text`, with the outline, shadow and plate of `style`. the plate covers
+// `FontRegistry::measure_text` of `text`, colors are faded with the menu
+pub fn draw_styled_text(
+    menu: &Menu,
+    frame: &mut Frame,
+    font: FontId,
+    text: &str,
+    baseline: Vertex,
+    size: f32,
+    style: &text_style::TextStyle
+) -> Result<(), MenuError> {
+    if style.plate.is_some() {
+        let metrics = menu.fonts.measure_text(font, text, size);
+        let bounds = Rect::new(
+            Vertex { p: [ baseline.p[0] - menu.offset.0, baseline.p[1] - metrics.ascent - menu.offset.1 ] },
+            metrics.width,
+            metrics.height
+        );
+        style.draw_plate(menu, frame, &bounds)?;
+    }
+    menu.fonts.transient(&menu.system, font, text)?.draw_styled(menu, frame, baseline, size, style)
+}
+
+fn read_font(path: &Path) -> Result<Vec<u8>, MenuError> {
+    std::fs::read(path).map_err(|error| MenuError::FontFile(path.to_path_buf(), error))
+}
@@ -0,0 +1,204 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+
+use glium::Frame;
+
+use crate::{ Menu, Rect, Vertex, fonts, primitives, error::MenuError };
+
+// how many frames the averages are taken over
+const FRAME_HISTORY: usize = 120;
+// text size of the stats widget in pixels
+const WIDGET_TEXT_SIZE: f32 = 14.0;
+const WIDGET_MARGIN: f32 = 8.0;
+
+#[derive(Copy, Clone, Debug, Default)]
+struct FrameSample {
+    // seconds since the frame before it was drawn
+    interval: f32,
+    // seconds spent building it, without waiting for the buffer swap
+    draw_time: f32,
+    draw_calls: u32,
+}
+
+// timings of the last frames the menu drew, frames skipped while idle aren't counted
+#[derive(Default)]
+pub struct FrameStats {
+    samples: VecDeque<FrameSample>,
+    // draw calls of the frame currently being built, counted by `primitives` and labels
+    draw_calls: Cell<u32>,
+    frames: u64,
+    // draws `draw_widget` in the top left corner every frame
+    pub show: bool,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn count_draw_call(&self) {
+        self.draw_calls.set(self.draw_calls.get() + 1);
+    }
+    pub(crate) fn end_frame(&mut self, interval: f32, draw_time: f32) {
+        if self.samples.len() == FRAME_HISTORY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(FrameSample {
+            interval,
+            draw_time,
+            draw_calls: self.draw_calls.replace(0),
+        });
+        self.frames += 1;
+    }
+    // forgets the draw calls of a frame that isn't recorded
+    pub(crate) fn discard_frame(&self) {
+        self.draw_calls.set(0);
+    }
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.draw_calls.set(0);
+        self.frames = 0;
+    }
+    // frames drawn since the menu was created or `reset`
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+    // frame times are in seconds
+    pub fn average_frame_time(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0
+        }
+        self.samples.iter().map(|sample| sample.interval).sum::<f32>() / self.samples.len() as f32
+    }
+    pub fn min_frame_time(&self) -> f32 {
+        self.samples.iter().map(|sample| sample.interval).reduce(f32::min).unwrap_or(0.0)
+    }
+    pub fn max_frame_time(&self) -> f32 {
+        self.samples.iter().map(|sample| sample.interval).reduce(f32::max).unwrap_or(0.0)
+    }
+    pub fn fps(&self) -> f32 {
+        let average = self.average_frame_time();
+        if average > 0.0 { 1.0 / average } else { 0.0 }
+    }
+    // the part of the frame time the menu itself took, the rest is waiting on pacing or vsync
+    pub fn average_draw_time(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0
+        }
+        self.samples.iter().map(|sample| sample.draw_time).sum::<f32>() / self.samples.len() as f32
+    }
+    // draw calls of the last finished frame
+    pub fn draw_calls(&self) -> u32 {
+        self.samples.back().map_or(0, |sample| sample.draw_calls)
+    }
+    pub fn summary(&self) -> Vec<String> {
+        vec![
+            format!("{:.0} fps", self.fps()),
+            format!(
+                "{:.2} ms (min {:.2}, max {:.2})",
+                self.average_frame_time() * 1000.0,
+                self.min_frame_time() * 1000.0,
+                self.max_frame_time() * 1000.0
+            ),
+            format!("draw {:.2} ms, {} calls", self.average_draw_time() * 1000.0, self.draw_calls()),
+        ]
+    }
+}
+
+// a small panel with `FrameStats::summary` in the top left corner of the overlay
+pub fn draw_widget(menu: &mut Menu, frame: &mut Frame) -> Result<(), MenuError> {
+    let lines = menu.stats.summary();
+    let line_height = WIDGET_TEXT_SIZE * 1.3;
+    let font = menu.fonts.default_font();
+
+    let text_width = lines.iter()
+        .map(|line| menu.fonts.text_width(font, line) * WIDGET_TEXT_SIZE)
+        .fold(0.0, f32::max);
+    let panel = Rect::new(
+        Vertex { p: [ WIDGET_MARGIN, WIDGET_MARGIN ] },
+        text_width + menu.theme.padding * 2.0,
+        line_height * lines.len() as f32 + menu.theme.padding * 2.0
+    );
+    primitives::fill_rounded_rect(menu, frame, &panel, [menu.theme.rounding; 4], menu.theme.panel)?;
+
+    // every line centered vertically in its row, like the panel it follows the menu's offset
+    let ascent = menu.fonts.get(font).ascent() * WIDGET_TEXT_SIZE;
+    let color = primitives::faded(menu, menu.theme.text);
+    for (i, line) in lines.iter().enumerate() {
+        let baseline = Vertex { p: [
+            panel.top_left.p[0] + menu.theme.padding + menu.offset.0,
+            panel.top_left.p[1] + menu.theme.padding + line_height * i as f32
+                + (line_height - WIDGET_TEXT_SIZE) / 2.0 + ascent + menu.offset.1
+        ] };
+        fonts::draw_text(menu, frame, font, line, baseline, WIDGET_TEXT_SIZE, color)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn starts_empty() {
+        let stats = FrameStats::new();
+        assert_eq!(stats.frames(), 0);
+        assert_eq!(stats.average_frame_time(), 0.0);
+        assert_eq!(stats.min_frame_time(), 0.0);
+        assert_eq!(stats.max_frame_time(), 0.0);
+        assert_eq!(stats.fps(), 0.0);
+        assert_eq!(stats.draw_calls(), 0);
+    }
+
+    #[test]
+    fn averages_frames() {
+        let mut stats = FrameStats::new();
+        stats.end_frame(0.01, 0.002);
+        stats.end_frame(0.02, 0.004);
+        stats.end_frame(0.03, 0.006);
+        assert_eq!(stats.frames(), 3);
+        assert!((stats.average_frame_time() - 0.02).abs() < 1e-6);
+        assert_eq!(stats.min_frame_time(), 0.01);
+        assert_eq!(stats.max_frame_time(), 0.03);
+        assert!((stats.fps() - 50.0).abs() < 1e-3);
+        assert!((stats.average_draw_time() - 0.004).abs() < 1e-6);
+        assert_eq!(stats.summary().len(), 3);
+    }
+
+    #[test]
+    fn counts_draw_calls_per_frame() {
+        let mut stats = FrameStats::new();
+        for _ in 0 .. 3 {
+            stats.count_draw_call();
+        }
+        stats.end_frame(0.016, 0.001);
+        assert_eq!(stats.draw_calls(), 3);
+        stats.end_frame(0.016, 0.001);
+        assert_eq!(stats.draw_calls(), 0);
+
+        // a frame that isn't recorded doesn't add to the next one
+        stats.count_draw_call();
+        stats.discard_frame();
+        stats.count_draw_call();
+        stats.end_frame(0.016, 0.001);
+        assert_eq!(stats.draw_calls(), 1);
+    }
+
+    #[test]
+    fn keeps_only_recent_frames() {
+        let mut stats = FrameStats::new();
+        for _ in 0 .. 10 {
+            stats.end_frame(1.0, 0.0);
+        }
+        for _ in 0 .. FRAME_HISTORY {
+            stats.end_frame(0.5, 0.0);
+        }
+        // the slow frames dropped out of the history, the count keeps going
+        assert_eq!(stats.max_frame_time(), 0.5);
+        assert_eq!(stats.average_frame_time(), 0.5);
+        assert_eq!(stats.frames(), (FRAME_HISTORY + 10) as u64);
+
+        stats.reset();
+        assert_eq!(stats.frames(), 0);
+        assert_eq!(stats.average_frame_time(), 0.0);
+    }
+}
@@ -0,0 +1,380 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::{ Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, Draggable, ContextActions, label, context_menu, primitives, animation, error::MenuError };
+
+use glium::Frame;
+use glium::backend::Facade;
+use glium::texture::{ Texture2d, RawImage2d };
+
+#[derive(Debug)]
+pub enum Error {
+    /// The png could not be decoded.
+    Png(png::DecodingError),
+    /// The texture could not be created on the gpu.
+    Texture(glium::texture::TextureCreationError),
+    /// The pixel data is not `width * height * 4` bytes long, or that size doesn't fit in memory.
+    InvalidSize,
+    /// The png uses a color type we can't convert to rgba.
+    UnsupportedFormat,
+    /// An image with this name was already added to the atlas.
+    DuplicateName(String),
+}
+
+impl From<png::DecodingError> for Error {
+    fn from(error: png::DecodingError) -> Self {
+        Error::Png(error)
+    }
+}
+
+impl From<glium::texture::TextureCreationError> for Error {
+    fn from(error: glium::texture::TextureCreationError) -> Self {
+        Error::Texture(error)
+    }
+}
+
+// 8 bit rgba pixels, top row first
+#[derive(Clone, Debug)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32, data: Vec<u8>) -> Result<Self, Error> {
+        // in usize, so huge dimensions are refused instead of wrapping around
+        let size = (width as usize).checked_mul(height as usize).and_then(|pixels| pixels.checked_mul(4));
+        if size != Some(data.len()) {
+            return Err(Error::InvalidSize)
+        }
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+    // works with `include_bytes!("icon.png")`
+    pub fn from_png(bytes: &[u8]) -> Result<Self, Error> {
+        let mut decoder = png::Decoder::new(bytes);
+        // palettes and low bit depths become plain 8 bit channels
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+        let mut reader = decoder.read_info()?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let data = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer.chunks_exact(3).flat_map(|p| [ p[0], p[1], p[2], 255 ]).collect(),
+            png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|p| [ p[0], p[0], p[0], p[1] ]).collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|g| [ *g, *g, *g, 255 ]).collect(),
+            _ => return Err(Error::UnsupportedFormat),
+        };
+
+        RgbaImage::new(info.width, info.height, data)
+    }
+    pub fn to_texture<F: Facade>(&self, facade: &F) -> Result<Texture2d, Error> {
+        // uploaded top row first, so v = 0 is the top of the image like the font texture
+        let raw = RawImage2d::from_raw_rgba(self.data.clone(), (self.width, self.height));
+        Ok(Texture2d::new(facade, raw)?)
+    }
+}
+
+pub fn load_png<F: Facade>(facade: &F, bytes: &[u8]) -> Result<Texture2d, Error> {
+    RgbaImage::from_png(bytes)?.to_texture(facade)
+}
+
+pub fn load_rgba<F: Facade>(facade: &F, data: &[u8], width: u32, height: u32) -> Result<Texture2d, Error> {
+    RgbaImage::new(width, height, data.to_vec())?.to_texture(facade)
+}
+
+// many small images packed into one texture, looked up by name
+pub struct TextureAtlas {
+    pub texture: Rc<Texture2d>,
+    regions: HashMap<String, primitives::UvRect>,
+}
+
+impl TextureAtlas {
+    pub fn region(&self, name: &str) -> Option<primitives::UvRect> {
+        self.regions.get(name).copied()
+    }
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.regions.keys()
+    }
+}
+
+#[derive(Default)]
+pub struct TextureAtlasBuilder {
+    images: Vec<(String, RgbaImage)>,
+}
+
+impl TextureAtlasBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    // names have to be unique, they are how the regions are looked up again
+    pub fn add(&mut self, name: &str, image: RgbaImage) -> Result<(), Error> {
+        if self.images.iter().any(|(existing, _)| existing == name) {
+            return Err(Error::DuplicateName(String::from(name)))
+        }
+        self.images.push((String::from(name), image));
+        Ok(())
+    }
+    pub fn add_png(&mut self, name: &str, bytes: &[u8]) -> Result<(), Error> {
+        self.add(name, RgbaImage::from_png(bytes)?)
+    }
+    // packs the images in rows from tallest to shortest, the same way the font texture packs glyphs
+    pub fn build<F: Facade>(self, facade: &F) -> Result<TextureAtlas, Error> {
+        // a margin around each image to prevent bleeding when filtering
+        const MARGIN: u32 = 2;
+
+        // in u64, a few large images add up past u32
+        let area: u64 = self.images.iter()
+            .map(|(_, i)| (u64::from(i.width) + u64::from(MARGIN)) * (u64::from(i.height) + u64::from(MARGIN)))
+            .sum();
+        let widest = self.images.iter().map(|(_, i)| i.width.saturating_add(MARGIN * 2)).max().unwrap_or(1);
+        let width = widest.max((area as f64).sqrt() as u32).max(1).checked_next_power_of_two().ok_or(Error::InvalidSize)?;
+
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by(|a, b| self.images[*b].1.height.cmp(&self.images[*a].1.height));
+
+        let mut cursor = (0u32, 0u32);
+        let mut row_height = 0u32;
+        let mut placements = Vec::with_capacity(order.len());
+
+        for i in order {
+            let image = &self.images[i].1;
+            // carriage return if the image doesn't fit on this row
+            if cursor.0 + image.width + MARGIN * 2 > width {
+                cursor.0 = 0;
+                cursor.1 += row_height;
+                row_height = 0;
+            }
+            let position = (cursor.0 + MARGIN, cursor.1 + MARGIN);
+            placements.push((i, position));
+            cursor.0 = position.0 + image.width;
+            row_height = row_height.max(image.height + MARGIN);
+        }
+
+        let height = (cursor.1 + row_height + MARGIN).max(1).checked_next_power_of_two().ok_or(Error::InvalidSize)?;
+        let size = (width as usize).checked_mul(height as usize).and_then(|pixels| pixels.checked_mul(4));
+        let mut pixels = vec![0u8; size.ok_or(Error::InvalidSize)?];
+
+        let mut regions = HashMap::with_capacity(placements.len());
+        for (i, (x, y)) in placements {
+            let (name, image) = &self.images[i];
+            for row in 0..image.height {
+                let source = (row * image.width * 4) as usize;
+                let destination = (((y + row) as usize) * width as usize + x as usize) * 4;
+                let length = (image.width * 4) as usize;
+                pixels[destination..destination + length].copy_from_slice(&image.data[source..source + length]);
+            }
+            regions.insert(name.clone(), primitives::UvRect::new(
+                [ x as f32 / width as f32, y as f32 / height as f32 ],
+                [ (x + image.width) as f32 / width as f32, (y + image.height) as f32 / height as f32 ],
+            ));
+        }
+
+        let texture = RgbaImage::new(width, height, pixels)?.to_texture(facade)?;
+
+        Ok(TextureAtlas {
+            texture: Rc::new(texture),
+            regions,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum Fit {
+    #[default]
+    Stretch,
+    // the whole image is visible and centered, leaving empty space on the short side
+    Contain,
+    // the whole rect is covered, cropping the image on the long side
+    Cover,
+}
+
+pub struct Image {
+    options: MenuOptions,
+    pub rect: Rect,
+    texture: Rc<Texture2d>,
+    uv: primitives::UvRect,
+    tint: Option<Vec4>,
+    fit: Fit,
+    label: Option<label::Label>,
+    hover: Cell<animation::Tween>,
+    actions: Vec<context_menu::ContextAction>,
+}
+
+impl Image {
+    pub fn new(
+        options: MenuOptions,
+        rect: Rect,
+        texture: Rc<Texture2d>,
+        tint: Option<Vec4>,
+        label: Option<label::Label>
+    ) -> Self {
+        Self {
+            options,
+            rect,
+            texture,
+            uv: primitives::UvRect::default(),
+            tint,
+            fit: Fit::Stretch,
+            label,
+            hover: animation::hover_fade(),
+            actions: Vec::new(),
+        }
+    }
+    pub fn from_atlas(
+        options: MenuOptions,
+        rect: Rect,
+        atlas: &TextureAtlas,
+        name: &str,
+        tint: Option<Vec4>,
+        label: Option<label::Label>
+    ) -> Option<Self> {
+        let mut image = Image::new(options, rect, Rc::clone(&atlas.texture), tint, label);
+        image.uv = atlas.region(name)?;
+        Some(image)
+    }
+    // part of the texture to draw, for sprite sheets
+    pub fn set_uv(&mut self, uv: primitives::UvRect) {
+        self.uv = uv;
+    }
+    pub fn set_fit(&mut self, fit: Fit) {
+        self.fit = fit;
+    }
+    pub fn set_tint(&mut self, tint: Option<Vec4>) {
+        self.tint = tint;
+    }
+    pub fn add_context_action(&mut self, action: context_menu::ContextAction) {
+        self.actions.push(action);
+    }
+    // where the image ends up and which part of the texture is shown, after fitting
+    fn placement(&self) -> (Rect, primitives::UvRect) {
+        let image_width = self.texture.width() as f32 * self.uv.width();
+        let image_height = self.texture.height() as f32 * self.uv.height();
+        if image_width <= 0.0 || image_height <= 0.0 || self.rect.width <= 0.0 || self.rect.height <= 0.0 {
+            return (self.rect, self.uv)
+        }
+        let image_aspect = image_width / image_height;
+        let rect_aspect = self.rect.width / self.rect.height;
+
+        match self.fit {
+            Fit::Stretch => (self.rect, self.uv),
+            Fit::Contain => {
+                let (width, height) = if image_aspect > rect_aspect {
+                    (self.rect.width, self.rect.width / image_aspect)
+                } else {
+                    (self.rect.height * image_aspect, self.rect.height)
+                };
+                let top_left = Vertex { p: [
+                    self.rect.top_left.p[0] + (self.rect.width - width) / 2.0,
+                    self.rect.top_left.p[1] + (self.rect.height - height) / 2.0,
+                ] };
+                (Rect::new(top_left, width, height), self.uv)
+            },
+            Fit::Cover => {
+                let mut uv = self.uv;
+                if image_aspect > rect_aspect {
+                    let crop = self.uv.width() * (1.0 - rect_aspect / image_aspect) / 2.0;
+                    uv.min[0] += crop;
+                    uv.max[0] -= crop;
+                } else {
+                    let crop = self.uv.height() * (1.0 - image_aspect / rect_aspect) / 2.0;
+                    uv.min[1] += crop;
+                    uv.max[1] -= crop;
+                }
+                (self.rect, uv)
+            },
+        }
+    }
+}
+
+impl InBounds for Image {
+    fn in_bounds(
+        &self,
+        menu: &Menu
+    ) -> bool {
+        self.rect.in_bounds(menu)
+    }
+}
+
+impl Hovering for Image {
+    fn is_hovering(
+        &self,
+        menu: &mut Menu,
+        frame: &mut Frame,
+    ) -> Result<(), MenuError> {
+        self.rect.is_hovering_animated(menu, frame, &self.hover)
+    }
+}
+
+impl Draggable for Image {
+    fn is_dragging(
+        &mut self,
+        menu: &mut Menu,
+    ) {
+        if menu.mouse_pos.0 != menu.cached_mouse_pos.0 || menu.mouse_pos.1 != menu.cached_mouse_pos.1 {
+            // make so we cant move it out of base_me
+            let new_x = self.rect.top_left.p[0] + menu.mouse_pos.0 - menu.cached_mouse_pos.0;
+            let new_y = self.rect.top_left.p[1] + menu.mouse_pos.1 - menu.cached_mouse_pos.1;
+            if self.in_bounds(menu) && menu.dragging
+                && new_x < menu.base.rect.top_left.p[0] + menu.base.rect.width - self.rect.width
+                && new_x > menu.base.rect.top_left.p[0]
+                && new_y < menu.base.rect.top_left.p[1] + menu.base.rect.height - self.rect.height
+                && new_y > menu.base.rect.top_left.p[1]
+            {
+                self.rect.top_left.p[0] = new_x;
+                self.rect.top_left.p[1] = new_y;
+                menu.cached_mouse_pos = menu.mouse_pos;
+            }
+        }
+    }
+}
+
+impl Clicked for Image {
+    fn clicked(
+        &self,
+        menu: &mut Menu,
+        _frame: &mut Frame,
+    ) -> bool {
+        self.in_bounds(menu) && menu.clicked
+    }
+}
+
+impl ContextActions for Image {
+    fn context_actions(&self) -> Vec<context_menu::ContextAction> {
+        self.actions.clone()
+    }
+}
+
+impl Options for Image {
+    fn get_options(&self) -> MenuOptions {
+        self.options
+    }
+}
+
+impl Draw for Image {
+    fn draw(
+        &mut self,
+        menu: &mut Menu,
+        frame: &mut Frame
+    ) -> Result<(), MenuError> {
+        let (rect, uv) = self.placement();
+        let tint = self.tint.unwrap_or(Vec4::new(1.0, 1.0, 1.0, 1.0));
+
+        primitives::draw_textured_rect(menu, frame, &rect, &self.texture, uv, tint)?;
+
+        match &self.label {
+            Some(label) => label.draw(&self.rect, menu, frame)?,
+            None => (),
+        };
+
+        Ok(())
+    }
+}
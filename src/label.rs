@@ -1,81 +1,217 @@
-use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, rusttype };
+use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, fonts, text_block, text_style, error::MenuError };
+
+use std::cell::RefCell;
 
 use glium::{ Surface, uniform, Frame };
 
-#[derive(Default)]
-pub enum Direction {
+// where a label goes relative to the rect of the widget it belongs to
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Anchor {
+    // outside the rect, next to the side it is named after. above and below line up with the
+    // rect's left edge, left and right are centered on it vertically
     #[default]
     Top,
     Left,
     Right,
     Bottom,
+    // inside the rect, in the middle
+    Center,
+    // inside the rect against its left edge and centered vertically, like the text of a list entry
+    CenterLeft,
 }
 
+// text next to or inside a widget. sizes and offsets are in logical pixels, like the rest of
+// the menu, and lines are snapped to whole physical pixels so glyphs stay sharp
 #[derive(Default)]
 pub struct Label {
-    color: Vec4,
-    direction: Direction,
-    pub original_text: String,
+    color: Option<Vec4>,
+    anchor: Anchor,
+    // the text the label was created with, `set_value` fills the `{}` in it
+    pub template: String,
     pub text: String,
-    scale: f32,
-    dist_scale: f32,
+    // height of an em in pixels
+    size: f32,
+    // moves the text from where its anchor puts it, positive goes right and down
+    offset: (f32, f32),
+    // None draws with the menu's default font
+    font: Option<fonts::FontId>,
+    // wraps the text into lines this many pixels wide
+    max_width: Option<f32>,
+    align: text_block::HAlign,
+    style: text_style::TextStyle,
+    // the laid out lines from the last draw, built again once anything they depend on changes
+    cache: RefCell<Option<LabelCache>>,
+}
+
+struct LabelCache {
+    text: String,
+    font: fonts::FontId,
+    size: f32,
+    max_width: Option<f32>,
+    // every line with its width in pixels
+    lines: Vec<(fonts::CachedText, f32)>,
+}
+
+impl LabelCache {
+    fn new(label: &Label, menu: &Menu, font: fonts::FontId) -> Result<Self, MenuError> {
+        let measure = |line: &str| menu.fonts.text_width(font, line) * label.size;
+        let lines = text_block::wrap_lines(&label.text, label.max_width, measure)
+            .iter()
+            .map(|line| {
+                let line = fonts::CachedText::new(&menu.fonts, &menu.system, font, line)?;
+                let width = line.width() * label.size;
+                Ok((line, width))
+            })
+            .collect::<Result<Vec<_>, MenuError>>()?;
+        Ok(Self {
+            text: label.text.clone(),
+            font,
+            size: label.size,
+            max_width: label.max_width,
+            lines,
+        })
+    }
+    fn is_current(&self, label: &Label, font: fonts::FontId) -> bool {
+        self.text == label.text && self.font == font && self.size == label.size && self.max_width == label.max_width
+    }
+    // lines are aligned within `max_width`, or the widest line without one
+    fn width(&self) -> f32 {
+        self.max_width.unwrap_or(self.lines.iter().map(|(_, width)| *width).fold(0.0, f32::max))
+    }
+    // from the top of the first line to the bottom of the last, like `FontRegistry::measure_text`
+    fn height(&self) -> f32 {
+        self.size * text_block::DEFAULT_LINE_SPACING * (self.lines.len().max(1) - 1) as f32 + self.size
+    }
 }
 
 impl Label {
-    pub fn new(color: Vec4, direction: Direction, text: &str, scale: f32, dist_scale: f32) -> Self {
+    pub fn new(color: Option<Vec4>, anchor: Anchor, text: &str, size: f32, offset: (f32, f32)) -> Self {
         Self {
             color,
-            direction,
-            original_text: String::from(text),
+            anchor,
+            template: String::from(text),
             text: String::from(text),
-            scale,
-            dist_scale
+            size,
+            offset,
+            font: None,
+            max_width: None,
+            align: text_block::HAlign::Left,
+            style: text_style::TextStyle::default(),
+            cache: RefCell::new(None),
         }
     }
+    // only lays the text out again when it actually changed
+    pub fn set_text(&mut self, text: &str) {
+        if self.text != text {
+            self.text = text.to_string();
+            *self.cache.get_mut() = None;
+        }
+    }
+    // shows `value` in place of the first `{}` of the template, or after it when it has none
+    pub fn set_value<T: std::fmt::Display>(&mut self, value: T) {
+        let text = if self.template.contains("{}") {
+            self.template.replacen("{}", &value.to_string(), 1)
+        } else {
+            format!("{}: {}", self.template, value)
+        };
+        self.set_text(&text);
+    }
+    pub fn with_font(mut self, font: fonts::FontId) -> Self {
+        self.font = Some(font);
+        self
+    }
+    pub fn with_wrap(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+    // lines are aligned within `max_width`, or the widest line without one
+    pub fn with_alignment(mut self, align: text_block::HAlign) -> Self {
+        self.align = align;
+        self
+    }
+    // outline, shadow and plate, the label's own color wins over the style's
+    pub fn with_style(mut self, style: text_style::TextStyle) -> Self {
+        self.style = style;
+        self
+    }
+    pub fn set_offset(&mut self, offset: (f32, f32)) {
+        self.offset = offset;
+    }
+    fn font(&self, menu: &Menu) -> fonts::FontId {
+        self.font.unwrap_or(menu.fonts.default_font())
+    }
+    // runs `f` with the cache, built again first when the label changed since it was built
+    fn with_layout<T>(&self, menu: &Menu, f: impl FnOnce(&LabelCache) -> T) -> Result<T, MenuError> {
+        let font = self.font(menu);
+        let mut cache = self.cache.borrow_mut();
+        // `text` is public, so a changed text is noticed here and not only in `set_text`
+        let layout = match cache.take() {
+            Some(layout) if layout.is_current(self, font) => layout,
+            _ => LabelCache::new(self, menu, font)?,
+        };
+        Ok(f(cache.insert(layout)))
+    }
+    // top left corner of text `size` pixels big, placed by the anchor next to the widget's `rect`
+    fn place(&self, rect: &Rect, size: (f32, f32)) -> Vertex {
+        let (x, y) = (rect.top_left.p[0], rect.top_left.p[1]);
+        let middle = y + (rect.height - size.1) / 2.0;
+        let (x, y) = match self.anchor {
+            Anchor::Top => (x, y - size.1),
+            Anchor::Left => (x - size.0, middle),
+            Anchor::Right => (x + rect.width, middle),
+            Anchor::Bottom => (x, y + rect.height),
+            Anchor::Center => (x + (rect.width - size.0) / 2.0, middle),
+            Anchor::CenterLeft => (x, middle),
+        };
+        Vertex { p: [ x + self.offset.0, y + self.offset.1 ] }
+    }
+    // the pixels the text covers next to the widget's `rect`, for layouts and hit testing
+    pub fn bounds(&self, rect: &Rect, menu: &Menu) -> Result<Rect, MenuError> {
+        let size = self.with_layout(menu, |layout| (layout.width(), layout.height()))?;
+        Ok(Rect::new(self.place(rect, size), size.0, size.1))
+    }
+    // false when the text can't be laid out
+    pub fn in_bounds(&self, rect: &Rect, menu: &Menu) -> bool {
+        self.bounds(rect, menu).is_ok_and(|bounds| bounds.in_bounds(menu))
+    }
     pub fn draw(
         &self,
         rect: &Rect,
         menu: &mut Menu,
         frame: &mut Frame,
-    ) {
-        let mut top_left = Vertex { p: [ rect.top_left.p[0], rect.top_left.p[1] ] };
-        match self.direction {
-            Direction::Top => {
-                top_left = Vertex { p: [ rect.top_left.p[0], rect.top_left.p[1] - rect.height * self.dist_scale] };
-            },
-            Direction::Left => {
-                top_left = Vertex { p: [ rect.top_left.p[0] - rect.width, rect.top_left.p[1] + rect.height * self.dist_scale ] };
-            },
-            Direction::Right => {
-                top_left = Vertex { p: [ rect.top_left.p[0] + rect.width, rect.top_left.p[1] + rect.height * self.dist_scale ] };
-            },
-            Direction::Bottom => {
-                top_left = Vertex { p: [ rect.top_left.p[0], rect.top_left.p[1] + rect.height * (self.dist_scale + 1.0) ] };
-            },
+    ) -> Result<(), MenuError> {
+        let style = text_style::TextStyle {
+            color: Some(self.color.or(self.style.color).unwrap_or(menu.theme.text)),
+            .. self.style
+        };
+        if style.plate.is_some() {
+            // the plate follows the menu's offset on its own
+            style.draw_plate(menu, frame, &self.bounds(rect, menu)?)?;
         }
-        let text = rusttype::TextDisplay::new(&menu.system, &menu.font, &self.text);
-        let text_width = text.get_width();
-
-        let sx = self.scale / (menu.window_size.0 as f32 / 2.0);
-        let sy = self.scale / (menu.window_size.1 as f32 / 2.0);
 
-        let x_ndc = (top_left.p[0] / menu.window_size.0 as f32) * 2.0 - 1.0;
-        let y_ndc = -((top_left.p[1] / menu.window_size.1 as f32) * 2.0 - 1.0);
+        let menu = &*menu;
+        self.with_layout(menu, |layout| -> Result<(), MenuError> {
+            let block_width = layout.width();
+            let mut top_left = self.place(rect, (block_width, layout.height()));
+            // follows the rest of the menu while it slides
+            top_left.p[0] += menu.offset.0;
+            top_left.p[1] += menu.offset.1;
+            let ascent = menu.fonts.get(layout.font).ascent() * self.size;
 
-        let matrix: [[f32; 4]; 4] = cgmath::Matrix4::new(
-            sx,  0.0, 0.0, 0.0,
-            0.0, sy,  0.0, 0.0,
-            0.0, 0.0, 1.0, 0.0,
-            x_ndc, y_ndc, 0.0, 1.0,
-        ).into();
-
-        rusttype::draw(
-            &text,
-            &menu.system,
-            frame,
-            matrix,
-            self.color.v
-                .into()
-        ).unwrap();
+            // further lines go below the first one
+            for (i, (line, width)) in layout.lines.iter().enumerate() {
+                let baseline = Vertex { p: [
+                    snap(menu, top_left.p[0] + text_block::align_offset(self.align, block_width, *width)),
+                    snap(menu, top_left.p[1] + ascent + self.size * text_block::DEFAULT_LINE_SPACING * i as f32)
+                ] };
+                line.draw_styled(menu, frame, baseline, self.size, &style)?;
+            }
+            Ok(())
+        })?
     }
 }
+
+// the nearest position on a whole physical pixel, glyphs in between are drawn blurry
+fn snap(menu: &Menu, position: f32) -> f32 {
+    (position * menu.scale_factor).round() / menu.scale_factor
+}
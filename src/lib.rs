@@ -1,11 +1,7 @@
-use windows::Win32::Foundation:: { HWND, RECT };
-use std::io::{Error, ErrorKind};
-use std::ffi::c_void;
-use winit::raw_window_handle::HasWindowHandle;
-
-use winit::{ window::{ Window, WindowAttributes, WindowLevel } , event_loop::EventLoop };
-
-use winit::dpi::{ Position::Logical, LogicalSize, LogicalPosition };
+use windows::Win32::Foundation::HWND;
+use std::cell::Cell;
+use std::time::Instant;
+use winit::{ window::Window, event_loop::EventLoop, event::WindowEvent };
 
 use glium::{ Surface, Frame };
 
@@ -28,60 +24,47 @@ pub mod filled_box;
 pub mod float_slider;
 pub mod label;
 pub mod line_strip;
-
+pub mod context_menu;
+pub mod theme;
+pub mod primitives;
+pub mod shape;
+pub mod image;
+pub mod fill;
+pub mod animation;
+pub mod window_tracker;
+pub mod window_finder;
+pub mod error;
+pub mod overlay;
+pub mod run_loop;
+pub mod frame_stats;
+pub mod fonts;
+pub mod text_block;
+pub mod text_style;
+pub mod system_fonts;
+
+use error::MenuError;
+
+// how far in pixels the mouse may move between a right press and release for it to count as a click
+const RIGHT_CLICK_SLOP: f32 = 2.0;
+// longest frame time fed to animations, so a stall doesn't skip them entirely
+const MAX_DELTA_TIME: f32 = 0.1;
+
+// shorthand for `overlay::OverlayBuilder` with the default options
 pub fn create_overlay(hwnd: Option<HWND>, overlay_name: &str) ->
     Result<(
         EventLoop<()>,
         Window,
         Display<WindowSurface>,
         HWND
-    ), Error>
+    ), MenuError>
 {
-    let mut window_size = RECT { left: 100, top: 100, right: 800, bottom: 800 };
-    let mut width = window_size.right - window_size.left - 15;
-    let mut height = window_size.bottom - window_size.top - 40;
-    if hwnd.is_some() {
-        window_size = windows_api::grab_window_dimensions(hwnd.unwrap());
-        width = window_size.right - window_size.left - 15;
-        height = window_size.bottom - window_size.top - 35;
-        window_size.left;
-    }
-    // calculating window dimensions
-
-    #[allow(deprecated)]
-    let window_attributes = WindowAttributes::new()
-        .with_title(overlay_name)
-        .with_inner_size(LogicalSize::new(width as f32, height as f32))
-        .with_position(Logical(LogicalPosition::new(window_size.left.into(), window_size.top.into())))
-        .with_transparent(true)
-        .with_decorations(true)
-        .with_window_level(WindowLevel::AlwaysOnTop);
-
-    let event_loop = glium::winit::event_loop::EventLoop::builder()
-        .build()
-        .expect("event loop failed to build!, probaly trying to build not within main thread");
-
-    let (window, display) = glium::backend::glutin::SimpleWindowBuilder::new()
-        .set_window_builder(window_attributes)
-        .build(&event_loop);
-
-    let window_handle = match window.window_handle() {
-        Ok(wh) => wh,
-        Err(_e) => return Err(Error::new(ErrorKind::Other, "Raw Window handle is invald!")),
-    };
-
-    let handle = match windows_api::grab_handle(window_handle) {
-        Some(h) => h,
-        None => return Err(Error::new(ErrorKind::Other, "HWND is invald!")),
-    };
-
-    let hwnd: winit::platform::windows::HWND = handle.into();
-
-    let overlay_handle = HWND(hwnd as *mut c_void);
-
-    windows_api::make_window_click_through(overlay_handle);
-
-    return Ok( (event_loop, window, display, overlay_handle) )
+    let mut builder = overlay::OverlayBuilder::new(overlay_name);
+    if let Some(hwnd) = hwnd {
+        builder = builder.attach_to(hwnd);
+    }
+    let overlay = builder.build()?;
+
+    Ok( (overlay.event_loop, overlay.window, overlay.display, overlay.handle) )
 }
 
 pub trait Draw {
@@ -89,7 +72,7 @@ pub trait Draw {
         &mut self,
         menu: &mut Menu,
         frame: &mut Frame
-    );
+    ) -> Result<(), MenuError>;
 }
 pub trait InBounds {
     fn in_bounds(
@@ -102,7 +85,7 @@ pub trait Hovering {
         &self,
         menu: &mut Menu,
         frame: &mut Frame,
-    );
+    ) -> Result<(), MenuError>;
 }
 pub trait Clicked {
     fn clicked(
@@ -120,19 +103,24 @@ pub trait Draggable {
         menu: &mut Menu,
     );
 }
+pub trait ContextActions {
+    fn context_actions(&self) -> Vec<context_menu::ContextAction>;
+}
 
 impl Draw for MenuObject {
     fn draw(
         &mut self,
         menu: &mut Menu,
         frame: &mut Frame
-    ) {
+    ) -> Result<(), MenuError> {
         match self {
             MenuObject::CheckBox(b) => b.draw(menu, frame),
             MenuObject::OutlineBox(b) => b.draw(menu, frame),
             MenuObject::FilledBox(b) => b.draw(menu, frame),
             MenuObject::FloatSlider(b) => b.draw(menu, frame),
+            MenuObject::Image(b) => b.draw(menu, frame),
             MenuObject::LineStrip(b) => b.draw(menu, frame),
+            MenuObject::Shape(b) => b.draw(menu, frame),
         }
     }
 }
@@ -146,6 +134,7 @@ impl InBounds for MenuObject {
             MenuObject::OutlineBox(b) => b.in_bounds(menu),
             MenuObject::FilledBox(b) => b.in_bounds(menu),
             MenuObject::FloatSlider(b) => b.in_bounds(menu),
+            MenuObject::Image(b) => b.in_bounds(menu),
             _ => false,
         }
     }
@@ -155,13 +144,14 @@ impl Hovering for MenuObject {
         &self,
         menu: &mut Menu,
         frame: &mut Frame,
-    ) {
+    ) -> Result<(), MenuError> {
         match self {
             MenuObject::CheckBox(b) => b.is_hovering(menu, frame),
             MenuObject::OutlineBox(b) => b.is_hovering(menu, frame),
             MenuObject::FilledBox(b) => b.is_hovering(menu, frame),
             MenuObject::FloatSlider(b) => b.is_hovering(menu, frame),
-            _ => (),
+            MenuObject::Image(b) => b.is_hovering(menu, frame),
+            _ => Ok(()),
         }
     }
 }
@@ -176,6 +166,7 @@ impl Clicked for MenuObject {
             MenuObject::OutlineBox(b) => b.clicked(menu, frame),
             MenuObject::FilledBox(b) => b.clicked(menu, frame),
             MenuObject::FloatSlider(b) => b.clicked(menu, frame),
+            MenuObject::Image(b) => b.clicked(menu, frame),
             _ => false,
         }
     }
@@ -187,7 +178,9 @@ impl Options for MenuObject {
             MenuObject::OutlineBox(b) => b.get_options(),
             MenuObject::FilledBox(b) => b.get_options(),
             MenuObject::FloatSlider(b) => b.get_options(),
+            MenuObject::Image(b) => b.get_options(),
             MenuObject::LineStrip(b) => b.get_options(),
+            MenuObject::Shape(b) => b.get_options(),
         }
     }
 }
@@ -201,11 +194,24 @@ impl Draggable for MenuObject {
             MenuObject::OutlineBox(b) => b.is_dragging(menu),
             MenuObject::FilledBox(b) => b.is_dragging(menu),
             MenuObject::FloatSlider(b) => b.is_dragging(menu),
+            MenuObject::Image(b) => b.is_dragging(menu),
             _ => (),
         }
     }
 }
-#[derive(Copy, Clone, Default)]
+impl ContextActions for MenuObject {
+    fn context_actions(&self) -> Vec<context_menu::ContextAction> {
+        match self {
+            MenuObject::CheckBox(b) => b.context_actions(),
+            MenuObject::OutlineBox(b) => b.context_actions(),
+            MenuObject::FilledBox(b) => b.context_actions(),
+            MenuObject::FloatSlider(b) => b.context_actions(),
+            MenuObject::Image(b) => b.context_actions(),
+            _ => Vec::new(),
+        }
+    }
+}
+#[derive(Copy, Clone, Default, Debug)]
 pub struct Vertex {
     pub p: [f32; 2],
 }
@@ -237,9 +243,11 @@ pub enum MenuObject {
     OutlineBox(outline_box::OutlineBox),
     FloatSlider(float_slider::FloatSlider),
     LineStrip(line_strip::LineStrip),
+    Shape(shape::Shape),
+    Image(image::Image),
 }
 
-#[derive(Default)]
+#[derive(Default, Copy, Clone, Debug, PartialEq)]
 pub struct Vec4 {
     v: [f32; 4]
 }
@@ -258,7 +266,7 @@ impl Vec4 {
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Copy, Clone, Debug)]
 pub struct Rect {
     pub top_left: Vertex,
     pub width: f32,
@@ -277,6 +285,14 @@ impl Rect {
             height,
         }
     }
+    // grows the rect by `amount` on every side, negative values shrink it
+    pub fn grow(&self, amount: f32) -> Rect {
+        Rect::new(
+            Vertex { p: [ self.top_left.p[0] - amount, self.top_left.p[1] - amount ] },
+            (self.width + amount * 2.0).max(0.0),
+            (self.height + amount * 2.0).max(0.0)
+        )
+    }
     pub fn in_bounds(
         &self,
         menu: &Menu
@@ -292,34 +308,98 @@ impl Rect {
         &self,
         menu: &mut Menu,
         frame: &mut Frame,
-    ) {
-        if self.in_bounds(menu) {
-            let top_left = Vertex { p: [ self.top_left.p[0] - 2.0, self.top_left.p[1] - 2.0] };
-            let mut outline = outline_box::OutlineBox::new(
-                MenuOptions::new(false, false, false, true),
-                Rect::new(top_left, self.width + 4.0, self.height + 4.0),
-                Vec4::new(1.0, 0.0, 0.0, 1.0),
-                4.0,
-                None
-            );
-            outline.draw(menu, frame);
+    ) -> Result<(), MenuError> {
+        let amount = if self.in_bounds(menu) { 1.0 } else { 0.0 };
+        self.draw_hover(menu, frame, amount)
+    }
+    // fades the hover outline in and out using a tween kept by the widget
+    pub fn is_hovering_animated(
+        &self,
+        menu: &mut Menu,
+        frame: &mut Frame,
+        hover: &Cell<animation::Tween>,
+    ) -> Result<(), MenuError> {
+        let target = if self.in_bounds(menu) { 1.0 } else { 0.0 };
+        let amount = animation::step(hover, target, menu.delta_time);
+        self.draw_hover(menu, frame, amount)
+    }
+    // the hover outline with its alpha scaled by `amount`
+    pub fn draw_hover(
+        &self,
+        menu: &mut Menu,
+        frame: &mut Frame,
+        amount: f32,
+    ) -> Result<(), MenuError> {
+        if amount <= 0.0 {
+            return Ok(())
         }
+        // the outline sits just outside of the rect
+        let width = menu.theme.hover_width;
+        let radius = if menu.theme.rounding > 0.0 { menu.theme.rounding + width / 2.0 } else { 0.0 };
+        let mut color = menu.theme.hover;
+        color.v[3] *= amount.min(1.0);
+        primitives::stroke_rounded_rect(
+            menu,
+            frame,
+            &self.grow(width / 2.0),
+            [radius; 4],
+            width,
+            color
+        )
     }
 }
 
 pub struct Menu {
     pub display: Display<WindowSurface>,
+    // logical size, what widget geometry, labels and the shaders work in
     pub window_size: (f32, f32),
+    // framebuffer size in pixels, `window_size` times `scale_factor`
+    pub physical_size: (u32, u32),
+    // 1.0 at 96 dpi, 1.5 on a 150% monitor
+    pub scale_factor: f32,
     pub system: rusttype::TextSystem,
-    pub font: rusttype::FontTexture,
+    // the font the menu was created with is `fonts.default_font()`
+    pub fonts: fonts::FontRegistry,
     pub handle: HWND,
     pub mouse_pos: (f32, f32),
     pub cached_mouse_pos: (f32, f32),
     pub base: filled_box::FilledBox,
+    pub theme: theme::Theme,
+    pub programs: primitives::Programs,
     objects: Vec<MenuObject>,
     pub clickthrough: bool,
     pub clicked: bool,
+    pub right_clicked: bool,
     pub dragging: bool,
+    right_press_pos: (f32, f32),
+    pub context_menu: Option<context_menu::ContextMenu>,
+    // seconds since the last frame, what animations advance by
+    pub delta_time: f32,
+    last_frame: Option<Instant>,
+    // fades the menu in and out when the overlay is toggled, 0 is hidden and 1 fully shown
+    pub fade: animation::Tween,
+    // how far in pixels the menu slides up while fading out
+    pub slide_distance: f32,
+    // alpha multiplier and pixel offset applied to everything currently being drawn
+    pub opacity: f32,
+    pub offset: (f32, f32),
+    // follows the target window when set, see `track_window`
+    pub tracker: Option<window_tracker::WindowTracker>,
+    // what happened to the tracked window since the last frame
+    pub target_events: Vec<window_tracker::TargetEvent>,
+    // how often `run` draws
+    pub pacing: run_loop::FramePacing,
+    // lets `run` stop drawing while the menu is hidden and there are no one frame drawings,
+    // the update callback keeps running at a low rate so hotkeys still work
+    pub idle_when_hidden: bool,
+    // frame times and draw calls, `stats.show` draws them on the overlay
+    pub stats: frame_stats::FrameStats,
+    // something on screen may be out of date even though the menu is hidden
+    redraw: bool,
+    // the last error `draw_menu` ran into, see `take_error`
+    error: Option<MenuError>,
+    // events `poll_tracker` found between frames, handed out with the next frame's
+    polled_events: Vec<window_tracker::TargetEvent>,
 }
 
 impl Menu {
@@ -330,75 +410,188 @@ impl Menu {
         handle: HWND,
         base_size: (f32, f32)
     ) -> Self {
+        Menu::try_new(display, system, font, handle, base_size).unwrap()
+    }
+    pub fn try_new(
+        display: Display<WindowSurface>,
+        system: rusttype::TextSystem,
+        font: rusttype::FontTexture,
+        handle: HWND,
+        base_size: (f32, f32)
+    ) -> Result<Self, MenuError> {
 
-        let window_size = display.get_framebuffer_dimensions();
-        let base = filled_box::FilledBox::new(
+        let physical_size = display.get_framebuffer_dimensions();
+        let scale_factor = windows_api::grab_scale_factor(handle);
+        let programs = primitives::Programs::try_new(&display)?;
+        let base = filled_box::FilledBox::panel(
             MenuOptions::new(true, true, false, false),
             Rect::new(Vertex { p: [ 100.0, 100.0] }, base_size.0, base_size.1),
-            Vec4::new(0.5, 0.5, 0.5, 1.0),
-            None
         );
 
-        Self {
+        Ok(Self {
             display,
-            window_size: (window_size.0 as f32, window_size.1 as f32),
+            window_size: (physical_size.0 as f32 / scale_factor, physical_size.1 as f32 / scale_factor),
+            physical_size,
+            scale_factor,
             system,
-            font,
+            fonts: fonts::FontRegistry::new(font),
             handle,
             mouse_pos: (0.0, 0.0),
             cached_mouse_pos: (0.0, 0.0),
             base,
+            theme: theme::Theme::default(),
+            programs,
             objects: Vec::new(),
             clickthrough: true,
             clicked: false,
+            right_clicked: false,
             dragging: false,
-        }
+            right_press_pos: (0.0, 0.0),
+            context_menu: None,
+            delta_time: 0.0,
+            last_frame: None,
+            fade: animation::Tween::new(0.0, animation::FADE_DURATION, animation::Easing::QuadOut),
+            slide_distance: 20.0,
+            opacity: 1.0,
+            offset: (0.0, 0.0),
+            tracker: None,
+            target_events: Vec::new(),
+            pacing: run_loop::FramePacing::default(),
+            idle_when_hidden: false,
+            stats: frame_stats::FrameStats::new(),
+            redraw: true,
+            error: None,
+            polled_events: Vec::new(),
+        })
+    }
+    // runs the event loop until the window is closed, `FrameContext::exit` is called or a frame
+    // fails to draw, whose error is returned. `update` runs before every frame and is the place
+    // to add one frame drawings
+    pub fn run<F>(self, event_loop: EventLoop<()>, window: Window, update: F) -> Result<(), MenuError>
+    where
+        F: FnMut(&mut Menu, &mut run_loop::FrameContext)
+    {
+        run_loop::run(self, event_loop, window, update)
     }
+    // a failed frame is skipped and its error kept for `take_error`, use `try_draw_menu` to
+    // handle every error yourself
     pub fn draw_menu(&mut self) {
+        if let Err(error) = self.try_draw_menu() {
+            self.error = Some(error);
+        }
+    }
+    // the last error `draw_menu` skipped a frame for since this was last called
+    pub fn take_error(&mut self) -> Option<MenuError> {
+        self.error.take()
+    }
+    pub fn try_draw_menu(&mut self) -> Result<(), MenuError> {
+        let started = Instant::now();
+        let interval = self.update_delta_time();
+        self.update_tracker();
         self.check_clicks();
+        self.fade.update(self.delta_time);
 
         let mut frame = self.display.draw();
 
         frame.clear_color(0.0, 0.0, 0.0, 0.0);
 
-        if !self.clickthrough {
+        // glium panics when a frame is dropped unfinished, so it is finished even if drawing failed
+        let drawn = self.draw_frame(&mut frame);
+        // taken before the buffer swap, which waits for vsync
+        let draw_time = started.elapsed().as_secs_f32();
+        let finished = frame.finish().map_err(MenuError::from);
+
+        match interval {
+            Some(interval) => self.stats.end_frame(interval, draw_time),
+            // a first frame has no interval, its draw calls aren't counted towards the next one
+            None => self.stats.discard_frame(),
+        }
+        self.fonts.end_frame();
+
+        drawn.and(finished)
+    }
+    // false while the next frame would look exactly like the last one, an empty window
+    pub fn needs_redraw(&self) -> bool {
+        let hidden = self.clickthrough && self.fade.is_finished() && self.fade.value() <= 0.0;
+        !hidden
+            || self.redraw
+            || self.stats.show
+            || self.context_menu.is_some()
+            || self.objects.iter().any(|object| object.get_options().delete)
+    }
+    // the bookkeeping of `draw_menu` for a frame that isn't drawn
+    pub fn skip_frame(&mut self) {
+        self.update_tracker();
+        // the time spent idle isn't a frame, animations and stats start over on the next one
+        self.last_frame = None;
+    }
+    // keeps drawing after an error so every object is put back, the first error is returned
+    fn draw_frame(&mut self, frame: &mut Frame) -> Result<(), MenuError> {
+        let mut result = Ok(());
+
+        // still drawn while fading out, but only interactive while the overlay takes input
+        let menu_visible = !self.clickthrough || self.fade.value() > 0.0;
+        let menu_opacity = self.fade.value().clamp(0.0, 1.0);
+        let menu_offset = (0.0, -(1.0 - menu_opacity) * self.slide_distance);
+
+        if menu_visible {
             let mut base = std::mem::take(&mut self.base);
 
-            base.draw(self, &mut frame);
-            self.base_dragging(&mut base);
-            let mut not_hover = false;
-            for object in self.objects.iter() {
-                if object.in_bounds(self) {
-                    not_hover = true;
+            self.opacity = menu_opacity;
+            self.offset = menu_offset;
+            result = result.and(base.draw(self, frame));
+            if !self.clickthrough {
+                self.base_dragging(&mut base);
+                let mut not_hover = false;
+                for object in self.objects.iter() {
+                    if object.in_bounds(self) {
+                        not_hover = true;
+                    }
+                }
+                if !not_hover {
+                    result = result.and(base.is_hovering(self, frame));
                 }
-            }
-            if !not_hover {
-                base.is_hovering(self, &mut frame);
             }
 
             self.base = base;
         }
 
+        self.update_context_menu();
+
         let mut objects = std::mem::take(&mut self.objects);
 
         let mut remove = vec![];
+        // whatever gets drawn now has to be cleared by another frame
+        self.redraw = menu_visible || self.context_menu.is_some();
 
         for (i, object) in objects.iter_mut().enumerate() {
             let options = object.get_options();
 
-            if options.delete || !self.clickthrough {
-                object.draw(self, &mut frame);
+            if options.delete || menu_visible {
+                // one frame drawings aren't part of the menu so they don't fade with it
+                if options.delete {
+                    self.opacity = 1.0;
+                    self.offset = (0.0, 0.0);
+                } else {
+                    self.opacity = menu_opacity;
+                    self.offset = menu_offset;
+                }
+
+                result = result.and(object.draw(self, frame));
 
-                object.clicked(self, &mut frame);
+                if options.delete || !self.clickthrough {
+                    object.clicked(self, frame);
 
-                if options.draggable {
-                    object.is_dragging(self);
-                }
-                if options.hover {
-                    object.is_hovering(self, &mut frame);
+                    if options.draggable {
+                        object.is_dragging(self);
+                    }
+                    if options.hover {
+                        result = result.and(object.is_hovering(self, frame));
+                    }
                 }
                 if options.delete {
-                    remove.push(i)
+                    remove.push(i);
+                    self.redraw = true;
                 }
             }
         }
@@ -411,7 +604,61 @@ impl Menu {
 
         self.objects = objects;
 
-        frame.finish().unwrap();
+        // drawn last so it sits on top of every widget
+        if let Some(mut context_menu) = self.context_menu.take() {
+            self.opacity = menu_opacity;
+            self.offset = menu_offset;
+            result = result.and(context_menu.draw(self, frame));
+            self.context_menu = Some(context_menu);
+        }
+        self.opacity = 1.0;
+        self.offset = (0.0, 0.0);
+
+        if self.stats.show {
+            result = result.and(frame_stats::draw_widget(self, frame));
+            self.redraw = true;
+        }
+
+        result
+    }
+    // keeps the sizes and mouse position in sync with the window, pass it every window event
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match event {
+            WindowEvent::Resized(size) => self.resize((size.width, size.height)),
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => self.set_scale_factor(*scale_factor as f32),
+            WindowEvent::CursorMoved { position, .. } => {
+                self.mouse_pos = (position.x as f32 / self.scale_factor, position.y as f32 / self.scale_factor);
+                self.redraw = true;
+            },
+            WindowEvent::MouseInput { .. } | WindowEvent::MouseWheel { .. } | WindowEvent::KeyboardInput { .. } => {
+                self.redraw = true;
+            },
+            _ => (),
+        }
+    }
+    pub fn resize(&mut self, physical_size: (u32, u32)) {
+        // minimized
+        if physical_size.0 == 0 || physical_size.1 == 0 {
+            return
+        }
+        self.display.resize(physical_size);
+        self.physical_size = physical_size;
+        self.update_window_size();
+        self.redraw = true;
+    }
+    pub fn set_scale_factor(&mut self, scale_factor: f32) {
+        if scale_factor <= 0.0 {
+            return
+        }
+        self.scale_factor = scale_factor;
+        self.update_window_size();
+        self.redraw = true;
+    }
+    fn update_window_size(&mut self) {
+        self.window_size = (
+            self.physical_size.0 as f32 / self.scale_factor,
+            self.physical_size.1 as f32 / self.scale_factor
+        );
     }
     pub fn add_to_draw_list(&mut self, object: MenuObject) {
         self.objects.push(object);
@@ -419,10 +666,12 @@ impl Menu {
     pub fn toggle_overlay(&mut self) {
         if self.clickthrough {
             windows_api::make_window_non_click_through(self.handle);
-            self.clickthrough = false
+            self.clickthrough = false;
+            self.fade.set_target(1.0);
         } else {
             windows_api::make_window_click_through(self.handle);
             self.clickthrough = true;
+            self.fade.set_target(0.0);
         }
         // https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-sendinput
         let mut inputs = [INPUT { r#type: INPUT_TYPE(0), Anonymous: INPUT_0::default() }; 2];
@@ -435,12 +684,36 @@ impl Menu {
 
         unsafe { SendInput(&inputs, std::mem::size_of_val::<INPUT>(&inputs[0]) as i32) };
     }
+    // keeps the overlay over `target`'s client area, hiding it while the target is minimized or in the background
+    pub fn track_window(&mut self, target: HWND) {
+        self.tracker = Some(window_tracker::WindowTracker::new(target, self.handle));
+    }
+    // follows the target window without drawing. the overlay stops getting redraws while
+    // the tracker has it hidden, so `run` calls this between frames to notice it coming back
+    pub fn poll_tracker(&mut self) {
+        if let Some(tracker) = &mut self.tracker {
+            self.polled_events.extend(tracker.update());
+        }
+    }
+    fn update_tracker(&mut self) {
+        self.poll_tracker();
+        self.target_events = std::mem::take(&mut self.polled_events);
+    }
+    // returns the unclamped time since the last frame, None on the first one
+    fn update_delta_time(&mut self) -> Option<f32> {
+        let now = Instant::now();
+        let interval = self.last_frame.map(|last| now.duration_since(last).as_secs_f32());
+        self.delta_time = interval.unwrap_or(0.0).min(MAX_DELTA_TIME);
+        self.last_frame = Some(now);
+        interval
+    }
     pub fn check_clicks(&mut self) {
         // reset for next loop if not clicked again
         unsafe {
             if self.clicked {
                 self.clicked = false;
             }
+            self.right_clicked = false;
             if GetAsyncKeyState(0x01) & 0x01 > 0 {
                 self.clicked = true;
             }
@@ -448,10 +721,55 @@ impl Menu {
                 if !self.dragging {
                     self.dragging = true;
                     self.cached_mouse_pos = self.mouse_pos;
+                    self.right_press_pos = self.mouse_pos;
                 }
             } else {
+                // released without moving, so it was a click and not a drag
+                if self.dragging
+                    && (self.mouse_pos.0 - self.right_press_pos.0).abs() <= RIGHT_CLICK_SLOP
+                    && (self.mouse_pos.1 - self.right_press_pos.1).abs() <= RIGHT_CLICK_SLOP
+                {
+                    self.right_clicked = true;
+                }
                 self.dragging = false;
             }
+            // escape
+            if GetAsyncKeyState(0x1B) & 0x01 > 0 {
+                self.context_menu = None;
+            }
+        }
+        // while the menu slides in its widgets are drawn away from where they are hit tested,
+        // so clicks would land on the wrong one
+        if !self.fade.is_finished() {
+            self.clicked = false;
+            self.right_clicked = false;
+        }
+    }
+    fn update_context_menu(&mut self) {
+        if self.clickthrough {
+            self.context_menu = None;
+            return
+        }
+        if let Some(context_menu) = &self.context_menu {
+            if self.clicked {
+                if let Some(i) = context_menu.entry_at(self) {
+                    context_menu.run(i);
+                }
+                // any click closes the menu and is not passed on to the widgets
+                self.context_menu = None;
+                self.clicked = false;
+            }
+        }
+        if self.right_clicked {
+            let actions = match self.objects.iter().rev().find(|object| object.in_bounds(self)) {
+                Some(object) => object.context_actions(),
+                None => Vec::new(),
+            };
+            if actions.is_empty() {
+                self.context_menu = None;
+            } else {
+                self.context_menu = Some(context_menu::ContextMenu::new(self.mouse_pos, actions));
+            }
         }
     }
     fn base_dragging(&mut self, base: &mut filled_box::FilledBox) {
@@ -484,6 +802,10 @@ impl Menu {
                             MenuObject::FloatSlider(b) => {
                                 b.rect.top_left.p[0] += self.mouse_pos.0 - self.cached_mouse_pos.0;
                                 b.rect.top_left.p[1] += self.mouse_pos.1 - self.cached_mouse_pos.1;
+                            },
+                            MenuObject::Image(b) => {
+                                b.rect.top_left.p[0] += self.mouse_pos.0 - self.cached_mouse_pos.0;
+                                b.rect.top_left.p[1] += self.mouse_pos.1 - self.cached_mouse_pos.1;
                             }
                             _ => (),
                         }
@@ -1,12 +1,12 @@
-use crate::{ MenuObject, Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, outline_box, Draggable, label };
+use crate::{ Menu, Vertex, Vec4, Draw, MenuOptions, Options, primitives, error::MenuError };
 
-use glium::{ Surface, uniform, Frame };
+use glium::Frame;
 
 pub struct LineStrip {
     options: MenuOptions,
     vertexs: Vec<Vertex>,
     color: Vec4,
-    thickness: f32,
+    style: primitives::StrokeStyle,
 }
 
 impl LineStrip {
@@ -20,9 +20,26 @@ impl LineStrip {
             options,
             vertexs,
             color,
-            thickness,
+            style: primitives::StrokeStyle::new(thickness),
         }
     }
+    pub fn set_join(&mut self, join: primitives::LineJoin) {
+        self.style.join = join;
+    }
+    pub fn set_cap(&mut self, cap: primitives::LineCap) {
+        self.style.cap = cap;
+    }
+    // connects the last point back to the first
+    pub fn set_closed(&mut self, closed: bool) {
+        self.style.closed = closed;
+    }
+    // width of the anti-aliased edge in pixels, 0 for hard edges
+    pub fn set_feather(&mut self, feather: f32) {
+        self.style.feather = feather;
+    }
+    pub fn set_style(&mut self, style: primitives::StrokeStyle) {
+        self.style = style;
+    }
 }
 
 impl Options for LineStrip {
@@ -36,59 +53,7 @@ impl Draw for LineStrip {
         &mut self,
         menu: &mut Menu,
         frame: &mut Frame
-    ) {
-        let uniforms = uniform! {
-            screen_size: [menu.window_size.0 as f32, menu.window_size.1 as f32],
-            color_input: [self.color.v[0], self.color.v[1], self.color.v[2], self.color.v[3]]
-        };
-
-        let vertex_buffer = glium::VertexBuffer::new(&menu.display, &self.vertexs).unwrap();
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::LineStrip);
-
-        let vertex_shader_src = r#"
-        #version 140
-
-        in vec2 p;
-        uniform vec2 screen_size;
-
-        void main() {
-        vec2 zero_to_one = p / screen_size;
-        vec2 zero_to_two = zero_to_one * 2.0;
-        vec2 clip_space = zero_to_two - 1.0;
-        clip_space.y = -clip_space.y;
-
-        gl_Position = vec4(clip_space, 0.0, 1.0);
-        }
-        "#;
-        let fragment_shader_src = r#"
-        #version 140
-
-        uniform vec4 color_input;
-        out vec4 color;
-
-        void main() {
-        color = color_input;
-        }
-        "#;
-
-        let params = glium::DrawParameters {
-            line_width: Some(self.thickness),
-            .. Default::default()
-        };
-
-        let program = glium::Program::from_source(
-            &menu.display,
-            vertex_shader_src,
-            fragment_shader_src,
-            None
-        ).unwrap();
-
-        frame.draw(
-            &vertex_buffer,
-            &indices,
-            &program,
-            &uniforms,
-            &params
-        ).unwrap();
+    ) -> Result<(), MenuError> {
+        primitives::draw_polyline(menu, frame, &self.vertexs, &self.style, self.color)
     }
 }
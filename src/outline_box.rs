@@ -1,24 +1,35 @@
-use crate::{ Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, Draggable, label };
-use glium::{ Surface, uniform, Frame };
+use crate::{ Rect, Menu, Vertex, Vec4, Draw, InBounds, Hovering, Clicked, MenuOptions, Options, Draggable, ContextActions, label, context_menu, primitives, error::MenuError };
+use glium::Frame;
 
 pub struct OutlineBox {
     options: MenuOptions,
     pub rect: Rect,
-    color: Vec4,
-    thickness: f32,
+    color: Option<Vec4>,
+    thickness: Option<f32>,
+    rounding: Option<[f32; 4]>,
     label: Option<label::Label>,
+    actions: Vec<context_menu::ContextAction>,
 }
 
 impl OutlineBox {
-    pub fn new(options: MenuOptions, rect: Rect, color: Vec4, thickness: f32, label: Option<label::Label>) -> Self {
+    pub fn new(options: MenuOptions, rect: Rect, color: Option<Vec4>, thickness: Option<f32>, label: Option<label::Label>) -> Self {
         Self {
             options,
             rect,
             color,
             thickness,
+            rounding: None,
             label,
+            actions: Vec::new(),
         }
     }
+    // per corner radius, top left, top right, bottom right, bottom left
+    pub fn set_rounding(&mut self, rounding: Option<[f32; 4]>) {
+        self.rounding = rounding;
+    }
+    pub fn add_context_action(&mut self, action: context_menu::ContextAction) {
+        self.actions.push(action);
+    }
 }
 
 impl InBounds for OutlineBox {
@@ -40,12 +51,13 @@ impl Hovering for OutlineBox {
         &self,
         menu: &mut Menu,
         frame: &mut Frame,
-    ) {
+    ) -> Result<(), MenuError> {
         if self.in_bounds(menu) {
             //let top_left = Vertex { position: [ top_left.position[0] - 2.0, top_left.position[1] - 2.0] };
             //let outline = OutlineBox::new(frame, top_left, width + 4.0, height + 4.0);
             //menu.add_to_draw_list(MenuObject::OutlineBox(outline));
         }
+        Ok(())
     }
 }
 
@@ -63,6 +75,12 @@ impl Clicked for OutlineBox {
     }
 }
 
+impl ContextActions for OutlineBox {
+    fn context_actions(&self) -> Vec<context_menu::ContextAction> {
+        self.actions.clone()
+    }
+}
+
 impl Options for OutlineBox {
     fn get_options(&self) -> MenuOptions {
         self.options
@@ -89,66 +107,18 @@ impl Draw for OutlineBox {
         &mut self,
         menu: &mut Menu,
         frame: &mut Frame
-    ) {
-        let uniforms = uniform! {
-            screen_size: [menu.window_size.0 as f32, menu.window_size.1 as f32],
-            color_input: [self.color.v[0], self.color.v[1], self.color.v[2], self.color.v[3]]
-        };
-
-        let shape = vec![
-            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] ] },
-            Vertex { p: [ self.rect.top_left.p[0] + self.rect.width, self.rect.top_left.p[1]] },
-            Vertex { p: [ self.rect.top_left.p[0] + self.rect.width, self.rect.top_left.p[1] + self.rect.height] },
-            Vertex { p: [ self.rect.top_left.p[0], self.rect.top_left.p[1] + self.rect.height] },
-        ];
-
-        let vertex_buffer = glium::VertexBuffer::new(&menu.display, &shape).unwrap();
-        let indices = glium::index::NoIndices(glium::index::PrimitiveType::LineLoop);
-
-        let vertex_shader_src = r#"
-        #version 140
+    ) -> Result<(), MenuError> {
+        let color = self.color.unwrap_or(menu.theme.border);
+        let thickness = self.thickness.unwrap_or(menu.theme.border_width);
+        let radii = self.rounding.unwrap_or([menu.theme.rounding; 4]);
 
-        in vec2 p;
-        uniform vec2 screen_size;
-
-        void main() {
-        vec2 zero_to_one = p / screen_size;
-        vec2 zero_to_two = zero_to_one * 2.0;
-        vec2 clip_space = zero_to_two - 1.0;
-        clip_space.y = -clip_space.y;
-
-        gl_Position = vec4(clip_space, 0.0, 1.0);
-        }
-        "#;
-        let fragment_shader_src = r#"
-        #version 140
-
-        uniform vec4 color_input;
-        out vec4 color;
-
-        void main() {
-        color = color_input;
-        }
-        "#;
-
-        let params = glium::DrawParameters {
-            line_width: Some(self.thickness),
-            .. Default::default()
-        };
-
-        let program = glium::Program::from_source(&menu.display, vertex_shader_src, fragment_shader_src, None).unwrap();
-
-        frame.draw(
-            &vertex_buffer,
-            &indices,
-            &program,
-            &uniforms,
-            &params
-        ).unwrap();
+        primitives::stroke_rounded_rect(menu, frame, &self.rect, radii, thickness, color)?;
 
         match &self.label {
-            Some(label) => label.draw(&self.rect, menu, frame),
+            Some(label) => label.draw(&self.rect, menu, frame)?,
             None => (),
         };
+
+        Ok(())
     }
 }
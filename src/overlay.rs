@@ -0,0 +1,239 @@
+use std::ffi::c_void;
+use std::num::NonZeroU32;
+use std::cmp::Reverse;
+
+use windows::Win32::Foundation::{ HWND, RECT };
+
+use winit::{ window::{ Window, WindowAttributes, WindowLevel }, event_loop::EventLoop };
+use winit::dpi::{ Position::Physical, PhysicalSize, PhysicalPosition };
+use winit::raw_window_handle::HasWindowHandle;
+
+use glium::backend::glutin::Display;
+
+use glutin::config::{ ConfigTemplateBuilder, GlConfig };
+use glutin::context::{ ContextAttributesBuilder, NotCurrentGlContext };
+use glutin::display::{ GetGlDisplay, GlDisplay };
+use glutin::surface::{ GlSurface, SurfaceAttributesBuilder, SwapInterval, WindowSurface };
+use glutin_winit::GlWindow;
+
+use crate::{ Menu, windows_api, rusttype, error::MenuError };
+
+// where the overlay opens when it isn't attached to a window or monitor, in physical pixels
+const DEFAULT_OVERLAY_RECT: RECT = RECT { left: 100, top: 100, right: 800, bottom: 800 };
+
+pub struct OverlayBuilder {
+    title: String,
+    target: Option<HWND>,
+    track_target: bool,
+    monitor: Option<usize>,
+    position: Option<(i32, i32)>,
+    size: Option<(u32, u32)>,
+    vsync: bool,
+    multisampling: u8,
+    transparent: bool,
+    // None picks borders only for a free floating window
+    decorations: Option<bool>,
+    click_through: bool,
+}
+
+impl OverlayBuilder {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            target: None,
+            track_target: false,
+            monitor: None,
+            position: None,
+            size: None,
+            vsync: true,
+            multisampling: 0,
+            transparent: true,
+            decorations: None,
+            click_through: true,
+        }
+    }
+    // covers the target's client area
+    pub fn attach_to(mut self, target: HWND) -> Self {
+        self.target = Some(target);
+        self.monitor = None;
+        self
+    }
+    // follows the target window after it moves or resizes, see `window_tracker`
+    pub fn track_target(mut self, track: bool) -> Self {
+        self.track_target = track;
+        self
+    }
+    // covers the whole monitor, 0 is the first one windows enumerates
+    pub fn fullscreen_on_monitor(mut self, monitor: usize) -> Self {
+        self.monitor = Some(monitor);
+        self.target = None;
+        self
+    }
+    // physical pixels, ignored when attached to a window or monitor
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+    // whether buffer swaps wait for the monitor refresh, on by default. turn it off for
+    // `FramePacing::Fps` or `FramePacing::Unlimited` above the refresh rate
+    pub fn with_vsync(mut self, vsync: bool) -> Self {
+        self.vsync = vsync;
+        self
+    }
+    // samples per pixel, 0 turns multisampling off
+    pub fn with_multisampling(mut self, samples: u8) -> Self {
+        self.multisampling = samples;
+        self
+    }
+    pub fn with_transparency(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = Some(decorations);
+        self
+    }
+    // starts letting mouse input through to the windows below
+    pub fn with_click_through(mut self, click_through: bool) -> Self {
+        self.click_through = click_through;
+        self
+    }
+    // screen rect the overlay's client area should cover
+    fn placement(&self) -> Result<RECT, MenuError> {
+        if let Some(target) = self.target {
+            if !windows_api::window_exists(target) {
+                return Err(MenuError::InvalidHandle)
+            }
+            return Ok(windows_api::grab_client_rect(target))
+        }
+        if let Some(monitor) = self.monitor {
+            return windows_api::monitor_rects()
+                .get(monitor)
+                .copied()
+                .ok_or_else(|| MenuError::WindowCreation(format!("there is no monitor {}", monitor)))
+        }
+        let (left, top) = self.position.unwrap_or((DEFAULT_OVERLAY_RECT.left, DEFAULT_OVERLAY_RECT.top));
+        let (width, height) = self.size.unwrap_or((
+            (DEFAULT_OVERLAY_RECT.right - DEFAULT_OVERLAY_RECT.left) as u32,
+            (DEFAULT_OVERLAY_RECT.bottom - DEFAULT_OVERLAY_RECT.top) as u32
+        ));
+        Ok(RECT { left, top, right: left + width as i32, bottom: top + height as i32 })
+    }
+    pub fn build(self) -> Result<Overlay, MenuError> {
+        let rect = self.placement()?;
+        let width = (rect.right - rect.left).max(1) as u32;
+        let height = (rect.bottom - rect.top).max(1) as u32;
+
+        // with its own borders the overlay couldn't line up with a window or monitor
+        let decorations = self.decorations.unwrap_or(self.target.is_none() && self.monitor.is_none());
+
+        let window_attributes = WindowAttributes::default()
+            .with_title(self.title.as_str())
+            .with_inner_size(PhysicalSize::new(width, height))
+            .with_position(Physical(PhysicalPosition::new(rect.left, rect.top)))
+            .with_transparent(self.transparent)
+            .with_decorations(decorations)
+            .with_window_level(WindowLevel::AlwaysOnTop);
+
+        let event_loop = EventLoop::builder().build()?;
+
+        let mut template = ConfigTemplateBuilder::new()
+            .with_alpha_size(8)
+            .with_transparency(self.transparent);
+        if self.multisampling > 0 {
+            template = template.with_multisampling(self.multisampling);
+        }
+
+        let transparent = self.transparent;
+        let samples = self.multisampling;
+        let (window, config) = glutin_winit::DisplayBuilder::new()
+            .with_window_attributes(Some(window_attributes))
+            .build(&event_loop, template, |configs| {
+                // prefer a config that can actually be transparent, then the fewest samples that are enough
+                configs
+                    .max_by_key(|config| (
+                        !transparent || config.supports_transparency().unwrap_or(false),
+                        config.num_samples() >= samples,
+                        Reverse(config.num_samples())
+                    ))
+                    .unwrap()
+            })
+            .map_err(|error| MenuError::WindowCreation(error.to_string()))?;
+        let window = window.ok_or_else(|| MenuError::WindowCreation(String::from("no window was created")))?;
+
+        let surface_attributes = window.build_surface_attributes(SurfaceAttributesBuilder::<WindowSurface>::new())
+            .map_err(|error| MenuError::WindowCreation(error.to_string()))?;
+        let surface = unsafe { config.display().create_window_surface(&config, &surface_attributes)? };
+
+        let raw_handle = window.window_handle().map_err(|_| MenuError::InvalidHandle)?.as_raw();
+        let context_attributes = ContextAttributesBuilder::new().build(Some(raw_handle));
+        let context = unsafe { config.display().create_context(&config, &context_attributes)? }
+            .make_current(&surface)?;
+
+        let interval = if self.vsync { SwapInterval::Wait(NonZeroU32::MIN) } else { SwapInterval::DontWait };
+        // not every driver lets you pick, that's not worth failing over
+        _ = surface.set_swap_interval(&context, interval);
+
+        let display = Display::from_context_surface(context, surface)
+            .map_err(|error| MenuError::WindowCreation(format!("{:?}", error)))?;
+
+        let window_handle = window.window_handle().map_err(|_| MenuError::InvalidHandle)?;
+        let handle = windows_api::grab_handle(window_handle).ok_or(MenuError::InvalidHandle)?;
+        let hwnd: winit::platform::windows::HWND = handle.into();
+        let handle = HWND(hwnd as *mut c_void);
+
+        if self.click_through {
+            windows_api::make_window_click_through(handle);
+        }
+
+        Ok(Overlay {
+            event_loop,
+            window,
+            display,
+            handle,
+            target: self.target,
+            track_target: self.track_target,
+        })
+    }
+}
+
+pub struct Overlay {
+    pub event_loop: EventLoop<()>,
+    pub window: Window,
+    pub display: Display<WindowSurface>,
+    pub handle: HWND,
+    // the window this overlay was attached to
+    pub target: Option<HWND>,
+    track_target: bool,
+}
+
+impl Overlay {
+    // a menu drawing into this overlay, with `font` rasterized at `font_size` for the ascii range
+    pub fn create_menu(&self, font: &[u8], font_size: u32, base_size: (f32, f32)) -> Result<Menu, MenuError> {
+        let font = rusttype::FontTexture::new(
+            &self.display,
+            font,
+            font_size,
+            rusttype::FontTexture::ascii_character_list()
+        )?;
+        self.menu_with_font(font, base_size)
+    }
+    // like `create_menu`, with the default font stored as distance fields so text is sharp at any
+    // size and every character of `font` can be drawn. needs a far smaller `font_size`, 32 is plenty
+    pub fn create_sdf_menu(&self, font: &[u8], font_size: u32, base_size: (f32, f32)) -> Result<Menu, MenuError> {
+        let font = rusttype::FontTexture::sdf(&self.display, font, font_size)?;
+        self.menu_with_font(font, base_size)
+    }
+    fn menu_with_font(&self, font: rusttype::FontTexture, base_size: (f32, f32)) -> Result<Menu, MenuError> {
+        let system = rusttype::TextSystem::try_new(&self.display)?;
+        let mut menu = Menu::try_new(self.display.clone(), system, font, self.handle, base_size)?;
+        if let (Some(target), true) = (self.target, self.track_target) {
+            menu.track_window(target);
+        }
+        Ok(menu)
+    }
+}
@@ -0,0 +1,1099 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::f32::consts::PI;
+
+use crate::{ Rect, Menu, Vertex, Vec4, fill, error::MenuError };
+
+use glium::{ Surface, uniform, Frame, implement_vertex };
+use glium::backend::Facade;
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ColorVertex {
+    pub p: [f32; 2],
+    pub c: [f32; 4],
+}
+
+implement_vertex!(ColorVertex, p, c);
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct TexturedVertex {
+    pub p: [f32; 2],
+    pub uv: [f32; 2],
+}
+
+implement_vertex!(TexturedVertex, p, uv);
+
+// part of a texture in 0..1 coordinates, with v = 0 being the top row of the image
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct UvRect {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+impl Default for UvRect {
+    fn default() -> Self {
+        Self {
+            min: [ 0.0, 0.0 ],
+            max: [ 1.0, 1.0 ],
+        }
+    }
+}
+
+impl UvRect {
+    pub fn new(min: [f32; 2], max: [f32; 2]) -> Self {
+        Self {
+            min,
+            max,
+        }
+    }
+    pub fn width(&self) -> f32 {
+        self.max[0] - self.min[0]
+    }
+    pub fn height(&self) -> f32 {
+        self.max[1] - self.min[1]
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Shadow {
+    pub offset: (f32, f32),
+    pub size: f32,
+    pub color: Vec4,
+}
+
+impl Shadow {
+    pub fn new(offset: (f32, f32), size: f32, color: Vec4) -> Self {
+        Self {
+            offset,
+            size,
+            color,
+        }
+    }
+}
+
+// shaders shared by everything drawn through this module, compiled once in `Menu::new`
+pub struct Programs {
+    flat: glium::Program,
+    colored: glium::Program,
+    textured: glium::Program,
+    gradient: glium::Program,
+    // gradient lookup textures by `Fill::stops_key`, so themes don't upload one every draw
+    gradients: RefCell<HashMap<Vec<u32>, glium::texture::Texture2d>>,
+}
+
+// more distinct gradients than this, e.g. animated ones, start the cache over
+const MAX_CACHED_GRADIENTS: usize = 64;
+
+impl Programs {
+    pub fn new<F: Facade>(facade: &F) -> Self {
+        Programs::try_new(facade).unwrap()
+    }
+    pub fn try_new<F: Facade>(facade: &F) -> Result<Self, MenuError> {
+        let flat_vertex_shader_src = r#"
+        #version 140
+
+        in vec2 p;
+        uniform vec2 screen_size;
+        uniform vec2 offset;
+
+        void main() {
+        vec2 zero_to_one = (p + offset) / screen_size;
+        vec2 zero_to_two = zero_to_one * 2.0;
+        vec2 clip_space = zero_to_two - 1.0;
+        clip_space.y = -clip_space.y;
+
+        gl_Position = vec4(clip_space, 0.0, 1.0);
+        }
+        "#;
+        let flat_fragment_shader_src = r#"
+        #version 140
+
+        uniform vec4 color_input;
+        out vec4 color;
+
+        void main() {
+        color = color_input;
+        }
+        "#;
+        let colored_vertex_shader_src = r#"
+        #version 140
+
+        in vec2 p;
+        in vec4 c;
+        uniform vec2 screen_size;
+        uniform vec2 offset;
+        out vec4 v_color;
+
+        void main() {
+        vec2 zero_to_one = (p + offset) / screen_size;
+        vec2 zero_to_two = zero_to_one * 2.0;
+        vec2 clip_space = zero_to_two - 1.0;
+        clip_space.y = -clip_space.y;
+
+        v_color = c;
+        gl_Position = vec4(clip_space, 0.0, 1.0);
+        }
+        "#;
+        let colored_fragment_shader_src = r#"
+        #version 140
+
+        in vec4 v_color;
+        uniform float alpha;
+        out vec4 color;
+
+        void main() {
+        color = vec4(v_color.rgb, v_color.a * alpha);
+        }
+        "#;
+
+        let textured_vertex_shader_src = r#"
+        #version 140
+
+        in vec2 p;
+        in vec2 uv;
+        uniform vec2 screen_size;
+        uniform vec2 offset;
+        out vec2 v_uv;
+
+        void main() {
+        vec2 zero_to_one = (p + offset) / screen_size;
+        vec2 zero_to_two = zero_to_one * 2.0;
+        vec2 clip_space = zero_to_two - 1.0;
+        clip_space.y = -clip_space.y;
+
+        v_uv = uv;
+        gl_Position = vec4(clip_space, 0.0, 1.0);
+        }
+        "#;
+        let textured_fragment_shader_src = r#"
+        #version 140
+
+        in vec2 v_uv;
+        uniform sampler2D tex;
+        uniform vec4 tint;
+        out vec4 color;
+
+        void main() {
+        color = texture(tex, v_uv) * tint;
+        }
+        "#;
+
+        let gradient_vertex_shader_src = r#"
+        #version 140
+
+        in vec2 p;
+        uniform vec2 screen_size;
+        uniform vec2 offset;
+        out vec2 v_p;
+
+        void main() {
+        vec2 zero_to_one = (p + offset) / screen_size;
+        vec2 zero_to_two = zero_to_one * 2.0;
+        vec2 clip_space = zero_to_two - 1.0;
+        clip_space.y = -clip_space.y;
+
+        v_p = p;
+        gl_Position = vec4(clip_space, 0.0, 1.0);
+        }
+        "#;
+        // radial when radius > 0, otherwise linear from start to end
+        let gradient_fragment_shader_src = r#"
+        #version 140
+
+        in vec2 v_p;
+        uniform vec2 start;
+        uniform vec2 end;
+        uniform float radius;
+        uniform sampler2D lookup;
+        uniform float alpha;
+        out vec4 color;
+
+        void main() {
+        float t;
+        if (radius > 0.0) {
+            t = distance(v_p, start) / radius;
+        } else {
+            vec2 line = end - start;
+            t = dot(v_p - start, line) / max(dot(line, line), 0.0001);
+        }
+        color = texture(lookup, vec2(clamp(t, 0.0, 1.0), 0.5));
+        color.a *= alpha;
+        }
+        "#;
+
+        Ok(Self {
+            flat: glium::Program::from_source(facade, flat_vertex_shader_src, flat_fragment_shader_src, None)?,
+            gradient: glium::Program::from_source(facade, gradient_vertex_shader_src, gradient_fragment_shader_src, None)?,
+            colored: glium::Program::from_source(facade, colored_vertex_shader_src, colored_fragment_shader_src, None)?,
+            textured: glium::Program::from_source(facade, textured_vertex_shader_src, textured_fragment_shader_src, None)?,
+            gradients: RefCell::new(HashMap::new()),
+        })
+    }
+}
+
+// number of segments used for a quarter circle of the given radius
+pub fn corner_segments(radius: f32) -> usize {
+    ((radius * 0.75).ceil() as usize).clamp(1, 24)
+}
+
+// outline of a rounded rect, clockwise from the top left corner.
+// radii are top left, top right, bottom right, bottom left and are clamped to half the smaller side.
+pub fn rounded_rect_path(rect: &Rect, radii: [f32; 4], segments: usize) -> Vec<Vertex> {
+    let max_radius = rect.width.min(rect.height).max(0.0) / 2.0;
+    let x = rect.top_left.p[0];
+    let y = rect.top_left.p[1];
+
+    // corner centers and the angle each quarter circle starts at, in screen space (y down)
+    let corners = [
+        (x, y, PI),
+        (x + rect.width, y, PI * 1.5),
+        (x + rect.width, y + rect.height, 0.0),
+        (x, y + rect.height, PI * 0.5),
+    ];
+    let signs = [ (1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0) ];
+
+    let mut path = Vec::with_capacity(4 * (segments + 1));
+    for (i, (cx, cy, start)) in corners.iter().enumerate() {
+        let radius = radii[i].clamp(0.0, max_radius);
+        let center = (cx + signs[i].0 * radius, cy + signs[i].1 * radius);
+        for s in 0..=segments {
+            let angle = start + (PI / 2.0) * (s as f32 / segments as f32);
+            path.push(Vertex { p: [ center.0 + angle.cos() * radius, center.1 + angle.sin() * radius ] });
+        }
+    }
+    path
+}
+
+// triangle list covering a convex outline
+pub fn fill_convex(path: &[Vertex]) -> Vec<Vertex> {
+    if path.len() < 3 {
+        return Vec::new()
+    }
+    let count = path.len() as f32;
+    let center = Vertex { p: [
+        path.iter().map(|v| v.p[0]).sum::<f32>() / count,
+        path.iter().map(|v| v.p[1]).sum::<f32>() / count,
+    ] };
+
+    let mut triangles = Vec::with_capacity(path.len() * 3);
+    for i in 0..path.len() {
+        triangles.push(center);
+        triangles.push(path[i]);
+        triangles.push(path[(i + 1) % path.len()]);
+    }
+    triangles
+}
+
+// triangle list filling the band between two closed outlines with the same number of points
+pub fn fill_ring(outer: &[Vertex], inner: &[Vertex]) -> Vec<Vertex> {
+    let count = outer.len().min(inner.len());
+    let mut triangles = Vec::with_capacity(count * 6);
+    for i in 0..count {
+        let next = (i + 1) % count;
+        triangles.extend_from_slice(&[ outer[i], outer[next], inner[next] ]);
+        triangles.extend_from_slice(&[ outer[i], inner[next], inner[i] ]);
+    }
+    triangles
+}
+
+// same as `fill_ring` but fades from `inner_color` to `outer_color`
+pub fn fill_ring_colored(outer: &[Vertex], outer_color: Vec4, inner: &[Vertex], inner_color: Vec4) -> Vec<ColorVertex> {
+    let o = |v: Vertex| ColorVertex { p: v.p, c: outer_color.v };
+    let i = |v: Vertex| ColorVertex { p: v.p, c: inner_color.v };
+
+    let count = outer.len().min(inner.len());
+    let mut triangles = Vec::with_capacity(count * 6);
+    for n in 0..count {
+        let next = (n + 1) % count;
+        triangles.extend_from_slice(&[ o(outer[n]), o(outer[next]), i(inner[next]) ]);
+        triangles.extend_from_slice(&[ o(outer[n]), i(inner[next]), i(inner[n]) ]);
+    }
+    triangles
+}
+
+pub fn rounded_rect(rect: &Rect, radii: [f32; 4]) -> Vec<Vertex> {
+    let segments = corner_segments(radii.iter().cloned().fold(0.0, f32::max));
+    fill_convex(&rounded_rect_path(rect, radii, segments))
+}
+
+// the border is centered on the rect's edge and generated as triangles,
+// so it keeps its thickness on drivers that clamp `line_width` to 1px
+pub fn rounded_rect_border(rect: &Rect, radii: [f32; 4], thickness: f32) -> Vec<Vertex> {
+    let half = thickness / 2.0;
+    let outer_rect = rect.grow(half);
+    let inner_rect = rect.grow(-half);
+    let outer_radii = radii.map(|r| if r > 0.0 { r + half } else { 0.0 });
+    let inner_radii = radii.map(|r| (r - half).max(0.0));
+
+    let segments = corner_segments(outer_radii.iter().cloned().fold(0.0, f32::max));
+    fill_ring(
+        &rounded_rect_path(&outer_rect, outer_radii, segments),
+        &rounded_rect_path(&inner_rect, inner_radii, segments),
+    )
+}
+
+pub fn drop_shadow(rect: &Rect, radius: f32, shadow: &Shadow) -> Vec<ColorVertex> {
+    let mut shadow_rect = *rect;
+    shadow_rect.top_left.p[0] += shadow.offset.0;
+    shadow_rect.top_left.p[1] += shadow.offset.1;
+
+    let outer_rect = shadow_rect.grow(shadow.size);
+    let outer_radius = radius + shadow.size;
+    let segments = corner_segments(outer_radius);
+
+    let inner = rounded_rect_path(&shadow_rect, [radius; 4], segments);
+    let outer = rounded_rect_path(&outer_rect, [outer_radius; 4], segments);
+
+    let mut transparent = shadow.color;
+    transparent.v[3] = 0.0;
+
+    let mut triangles: Vec<ColorVertex> = fill_convex(&inner).into_iter()
+        .map(|v| ColorVertex { p: v.p, c: shadow.color.v })
+        .collect();
+    triangles.extend(fill_ring_colored(&outer, transparent, &inner, shadow.color));
+    triangles
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Bevel,
+    Round,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Square,
+    Round,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    pub thickness: f32,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    pub closed: bool,
+    // width in pixels of the faded edge used for anti-aliasing, 0 turns it off
+    pub feather: f32,
+    // miter joins longer than this many half widths fall back to a bevel
+    pub miter_limit: f32,
+}
+
+impl StrokeStyle {
+    pub fn new(thickness: f32) -> Self {
+        Self {
+            thickness,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            closed: false,
+            feather: 1.0,
+            miter_limit: 4.0,
+        }
+    }
+}
+
+// a point along a stroke and the directions its left and right edges are pushed out in.
+// the directions are scaled so that multiplying by the half width lands on the edge
+#[derive(Copy, Clone)]
+struct StrokePair {
+    center: (f32, f32),
+    left: (f32, f32),
+    right: (f32, f32),
+}
+
+fn add(a: (f32, f32), b: (f32, f32)) -> (f32, f32) { (a.0 + b.0, a.1 + b.1) }
+fn sub(a: (f32, f32), b: (f32, f32)) -> (f32, f32) { (a.0 - b.0, a.1 - b.1) }
+fn scale(a: (f32, f32), s: f32) -> (f32, f32) { (a.0 * s, a.1 * s) }
+fn dot(a: (f32, f32), b: (f32, f32)) -> f32 { a.0 * b.0 + a.1 * b.1 }
+fn cross(a: (f32, f32), b: (f32, f32)) -> f32 { a.0 * b.1 - a.1 * b.0 }
+fn length(a: (f32, f32)) -> f32 { dot(a, a).sqrt() }
+fn normalize(a: (f32, f32)) -> (f32, f32) { scale(a, 1.0 / length(a)) }
+fn perp(a: (f32, f32)) -> (f32, f32) { (-a.1, a.0) }
+fn rotate(a: (f32, f32), angle: f32) -> (f32, f32) {
+    let (sin, cos) = angle.sin_cos();
+    (a.0 * cos - a.1 * sin, a.0 * sin + a.1 * cos)
+}
+
+// segments used to approximate an arc of `angle` radians at the given radius
+fn arc_segments(angle: f32, radius: f32) -> usize {
+    ((angle.abs() * radius.max(1.0).sqrt() * 1.5).ceil() as usize).clamp(2, 32)
+}
+
+fn push_cap(pairs: &mut Vec<StrokePair>, center: (f32, f32), direction: (f32, f32), cap: LineCap, half_width: f32, start: bool) {
+    let normal = perp(direction);
+    match cap {
+        LineCap::Butt => pairs.push(StrokePair { center, left: normal, right: scale(normal, -1.0) }),
+        LineCap::Square => {
+            let center = add(center, scale(direction, if start { -half_width } else { half_width }));
+            pairs.push(StrokePair { center, left: normal, right: scale(normal, -1.0) });
+        },
+        LineCap::Round => {
+            // both edges meet at the tip of the cap and open up to the normals
+            let segments = arc_segments(PI / 2.0, half_width);
+            for s in 0..=segments {
+                let t = s as f32 / segments as f32;
+                let pair = if start {
+                    let angle = (1.0 - t) * PI / 2.0;
+                    StrokePair { center, left: rotate(normal, angle), right: rotate(scale(normal, -1.0), -angle) }
+                } else {
+                    let angle = t * PI / 2.0;
+                    StrokePair { center, left: rotate(normal, -angle), right: rotate(scale(normal, -1.0), angle) }
+                };
+                pairs.push(pair);
+            }
+        },
+    }
+}
+
+fn push_join(pairs: &mut Vec<StrokePair>, center: (f32, f32), from: (f32, f32), to: (f32, f32), style: &StrokeStyle, half_width: f32) {
+    let normal_from = perp(from);
+    let normal_to = perp(to);
+    let sum = add(normal_from, normal_to);
+
+    // the path doubles back on itself
+    if length(sum) < 1e-4 {
+        pairs.push(StrokePair { center, left: normal_from, right: scale(normal_from, -1.0) });
+        pairs.push(StrokePair { center, left: normal_to, right: scale(normal_to, -1.0) });
+        return
+    }
+
+    let middle = normalize(sum);
+    let cos_half = dot(middle, normal_from).max(1e-4);
+    let miter = scale(middle, 1.0 / cos_half);
+    let turn = cross(from, to);
+
+    if turn.abs() < 1e-4 {
+        pairs.push(StrokePair { center, left: miter, right: scale(miter, -1.0) });
+        return
+    }
+
+    // the side the path turns toward only gets the miter point, clamped so sharp turns don't spike
+    let inner_sign = if turn > 0.0 { 1.0 } else { -1.0 };
+    let inner = scale(middle, inner_sign * (1.0 / cos_half).min(style.miter_limit));
+
+    let outer_from = scale(normal_from, -inner_sign);
+    let outer_to = scale(normal_to, -inner_sign);
+
+    let outer = match style.join {
+        LineJoin::Miter if 1.0 / cos_half <= style.miter_limit => vec![ scale(miter, -inner_sign) ],
+        LineJoin::Round => {
+            let angle = cross(outer_from, outer_to).atan2(dot(outer_from, outer_to));
+            let segments = arc_segments(angle, half_width);
+            (0..=segments).map(|s| rotate(outer_from, angle * s as f32 / segments as f32)).collect()
+        },
+        _ => vec![ outer_from, outer_to ],
+    };
+
+    for direction in outer {
+        if turn > 0.0 {
+            pairs.push(StrokePair { center, left: inner, right: direction });
+        } else {
+            pairs.push(StrokePair { center, left: direction, right: inner });
+        }
+    }
+}
+
+// turns a polyline into triangles. the line keeps its thickness on every driver since it
+// doesn't rely on `line_width`, and the edges fade out over `feather` pixels
+pub fn stroke_polyline(points: &[Vertex], style: &StrokeStyle, color: Vec4) -> Vec<ColorVertex> {
+    // consecutive duplicate points have no direction
+    let mut path: Vec<(f32, f32)> = Vec::with_capacity(points.len());
+    for point in points {
+        let point = (point.p[0], point.p[1]);
+        if path.last().is_none_or(|last| length(sub(point, *last)) > 1e-4) {
+            path.push(point);
+        }
+    }
+    if style.closed && path.len() > 2 && length(sub(path[0], path[path.len() - 1])) <= 1e-4 {
+        path.pop();
+    }
+    if path.len() < 2 || style.thickness <= 0.0 {
+        return Vec::new()
+    }
+
+    let half_width = style.thickness / 2.0;
+    let count = path.len();
+    let mut pairs = Vec::with_capacity(count * 2);
+
+    if style.closed && count > 2 {
+        for i in 0..count {
+            let previous = path[(i + count - 1) % count];
+            let next = path[(i + 1) % count];
+            push_join(&mut pairs, path[i], normalize(sub(path[i], previous)), normalize(sub(next, path[i])), style, half_width);
+        }
+        let first = pairs[0];
+        pairs.push(first);
+    } else {
+        push_cap(&mut pairs, path[0], normalize(sub(path[1], path[0])), style.cap, half_width, true);
+        for i in 1..count - 1 {
+            push_join(&mut pairs, path[i], normalize(sub(path[i], path[i - 1])), normalize(sub(path[i + 1], path[i])), style, half_width);
+        }
+        push_cap(&mut pairs, path[count - 1], normalize(sub(path[count - 1], path[count - 2])), style.cap, half_width, false);
+    }
+
+    let feather = style.feather.max(0.0);
+    let core = (half_width - feather / 2.0).max(0.0);
+    let edge = half_width + feather / 2.0;
+
+    let mut solid = color;
+    // lines thinner than the feather get fainter instead of thinner
+    if feather > 0.0 && style.thickness < feather {
+        solid.v[3] *= style.thickness / feather;
+    }
+    let mut clear = color;
+    clear.v[3] = 0.0;
+
+    let point = |center: (f32, f32), direction: (f32, f32), width: f32, color: Vec4| {
+        let p = add(center, scale(direction, width));
+        ColorVertex { p: [ p.0, p.1 ], c: color.v }
+    };
+
+    let mut triangles = Vec::with_capacity(pairs.len() * 18);
+    for window in pairs.windows(2) {
+        let (a, b) = (window[0], window[1]);
+
+        let a_left = point(a.center, a.left, core, solid);
+        let a_right = point(a.center, a.right, core, solid);
+        let b_left = point(b.center, b.left, core, solid);
+        let b_right = point(b.center, b.right, core, solid);
+        triangles.extend_from_slice(&[ a_left, b_left, b_right, a_left, b_right, a_right ]);
+
+        if feather > 0.0 {
+            let a_left_edge = point(a.center, a.left, edge, clear);
+            let b_left_edge = point(b.center, b.left, edge, clear);
+            triangles.extend_from_slice(&[ a_left_edge, b_left_edge, b_left, a_left_edge, b_left, a_left ]);
+
+            let a_right_edge = point(a.center, a.right, edge, clear);
+            let b_right_edge = point(b.center, b.right, edge, clear);
+            triangles.extend_from_slice(&[ a_right, b_right, b_right_edge, a_right, b_right_edge, a_right_edge ]);
+        }
+    }
+    triangles
+}
+
+pub fn draw_polyline(menu: &Menu, frame: &mut Frame, points: &[Vertex], style: &StrokeStyle, color: Vec4) -> Result<(), MenuError> {
+    draw_colored_triangles(menu, frame, &stroke_polyline(points, style, color))
+}
+
+// the first `fraction` (0..1) of an open path by length, used to draw lines in progressively
+pub fn trim_polyline(points: &[Vertex], fraction: f32) -> Vec<Vertex> {
+    let fraction = fraction.clamp(0.0, 1.0);
+    if fraction >= 1.0 || points.len() < 2 {
+        return points.to_vec()
+    }
+    let lengths: Vec<f32> = points.windows(2).map(|w| (w[1].p[0] - w[0].p[0]).hypot(w[1].p[1] - w[0].p[1])).collect();
+    let mut remaining = lengths.iter().sum::<f32>() * fraction;
+
+    let mut trimmed = vec![ points[0] ];
+    for (i, segment) in lengths.iter().enumerate() {
+        if remaining >= *segment {
+            trimmed.push(points[i + 1]);
+            remaining -= segment;
+            continue
+        }
+        if remaining > 0.0 {
+            let t = remaining / segment;
+            let (a, b) = (points[i].p, points[i + 1].p);
+            trimmed.push(Vertex { p: [ a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t ] });
+        }
+        break
+    }
+    trimmed
+}
+
+// segments for a full circle, enough that the edge looks round at the given radius
+pub fn circle_segments(radius: f32) -> usize {
+    ((radius.max(1.0).sqrt() * 6.0).ceil() as usize).clamp(12, 128)
+}
+
+// points along an elliptical arc. angles are in radians, clockwise on screen from the positive x axis
+pub fn ellipse_path(center: Vertex, radius: (f32, f32), start: f32, sweep: f32, segments: usize) -> Vec<Vertex> {
+    let segments = segments.max(1);
+    (0..=segments).map(|s| {
+        let angle = start + sweep * (s as f32 / segments as f32);
+        Vertex { p: [ center.p[0] + angle.cos() * radius.0, center.p[1] + angle.sin() * radius.1 ] }
+    }).collect()
+}
+
+// triangle list fanning out from `center` to each consecutive pair of points
+pub fn fill_fan(center: Vertex, points: &[Vertex]) -> Vec<Vertex> {
+    let mut triangles = Vec::with_capacity(points.len() * 3);
+    for window in points.windows(2) {
+        triangles.extend_from_slice(&[ center, window[0], window[1] ]);
+    }
+    triangles
+}
+
+pub fn ellipse(center: Vertex, radius: (f32, f32)) -> Vec<Vertex> {
+    let segments = circle_segments(radius.0.max(radius.1));
+    let mut path = ellipse_path(center, radius, 0.0, PI * 2.0, segments);
+    // the last point repeats the first one
+    path.pop();
+    fill_convex(&path)
+}
+
+// segments for part of a circle, in proportion to `circle_segments`
+pub fn sweep_segments(radius: f32, sweep: f32) -> usize {
+    ((circle_segments(radius) as f32 * sweep.abs() / (PI * 2.0)).ceil() as usize).max(1)
+}
+
+pub fn pie(center: Vertex, radius: f32, start: f32, sweep: f32) -> Vec<Vertex> {
+    fill_fan(center, &ellipse_path(center, (radius, radius), start, sweep, sweep_segments(radius, sweep)))
+}
+
+fn point_in_triangle(p: [f32; 2], a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> bool {
+    let side = |u: [f32; 2], v: [f32; 2]| (v[0] - u[0]) * (p[1] - u[1]) - (v[1] - u[1]) * (p[0] - u[0]);
+    let (d1, d2, d3) = (side(a, b), side(b, c), side(c, a));
+    let negative = d1 < 0.0 || d2 < 0.0 || d3 < 0.0;
+    let positive = d1 > 0.0 || d2 > 0.0 || d3 > 0.0;
+    !(negative && positive)
+}
+
+// triangle list for any simple polygon, convex or not, by ear clipping
+pub fn triangulate_polygon(points: &[Vertex]) -> Vec<Vertex> {
+    let count = points.len();
+    if count < 3 {
+        return Vec::new()
+    }
+
+    // the winding tells us which way round a convex corner turns
+    let area: f32 = (0..count).map(|i| {
+        let a = points[i].p;
+        let b = points[(i + 1) % count].p;
+        a[0] * b[1] - b[0] * a[1]
+    }).sum();
+    let winding = if area >= 0.0 { 1.0 } else { -1.0 };
+
+    let mut remaining: Vec<usize> = (0..count).collect();
+    let mut triangles = Vec::with_capacity((count - 2) * 3);
+
+    while remaining.len() > 3 {
+        let left = remaining.len();
+        let ear = (0..left).find(|&i| {
+            let (ia, ib, ic) = (remaining[(i + left - 1) % left], remaining[i], remaining[(i + 1) % left]);
+            let (a, b, c) = (points[ia].p, points[ib].p, points[ic].p);
+            let turn = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+            turn * winding > 0.0 && !remaining.iter().any(|&j| {
+                j != ia && j != ib && j != ic && point_in_triangle(points[j].p, a, b, c)
+            })
+        });
+
+        match ear {
+            Some(i) => {
+                let (ia, ib, ic) = (remaining[(i + left - 1) % left], remaining[i], remaining[(i + 1) % left]);
+                triangles.extend_from_slice(&[ points[ia], points[ib], points[ic] ]);
+                remaining.remove(i);
+            },
+            // self intersecting or fully degenerate, fan what is left rather than drop it
+            None => break,
+        }
+    }
+
+    for i in 1..remaining.len().saturating_sub(1) {
+        triangles.extend_from_slice(&[ points[remaining[0]], points[remaining[i]], points[remaining[i + 1]] ]);
+    }
+    triangles
+}
+
+fn blend_params<'a>() -> glium::DrawParameters<'a> {
+    glium::DrawParameters {
+        blend: glium::Blend::alpha_blending(),
+        .. Default::default()
+    }
+}
+
+// `color` with its alpha scaled by the opacity the menu is currently drawn at
+pub fn faded(menu: &Menu, color: Vec4) -> Vec4 {
+    let mut color = color;
+    color.v[3] *= menu.opacity;
+    color
+}
+
+// draws a triangle list in a single color
+pub fn draw_triangles(
+    menu: &Menu,
+    frame: &mut Frame,
+    triangles: &[Vertex],
+    color: Vec4
+) -> Result<(), MenuError> {
+    if triangles.is_empty() {
+        return Ok(())
+    }
+    let uniforms = uniform! {
+        screen_size: [menu.window_size.0, menu.window_size.1],
+        offset: [menu.offset.0, menu.offset.1],
+        color_input: faded(menu, color).v
+    };
+
+    let vertex_buffer = glium::VertexBuffer::new(&menu.display, triangles)?;
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+
+    frame.draw(
+        &vertex_buffer,
+        &indices,
+        &menu.programs.flat,
+        &uniforms,
+        &blend_params()
+    )?;
+    menu.stats.count_draw_call();
+    Ok(())
+}
+
+// draws a triangle list using each vertex's own color
+pub fn draw_colored_triangles(
+    menu: &Menu,
+    frame: &mut Frame,
+    triangles: &[ColorVertex]
+) -> Result<(), MenuError> {
+    if triangles.is_empty() {
+        return Ok(())
+    }
+    let uniforms = uniform! {
+        screen_size: [menu.window_size.0, menu.window_size.1],
+        offset: [menu.offset.0, menu.offset.1],
+        alpha: menu.opacity
+    };
+
+    let vertex_buffer = glium::VertexBuffer::new(&menu.display, triangles)?;
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+
+    frame.draw(
+        &vertex_buffer,
+        &indices,
+        &menu.programs.colored,
+        &uniforms,
+        &blend_params()
+    )?;
+    menu.stats.count_draw_call();
+    Ok(())
+}
+
+// draws the `uv` part of `texture` stretched over `rect`, multiplied by `tint`
+pub fn draw_textured_rect(
+    menu: &Menu,
+    frame: &mut Frame,
+    rect: &Rect,
+    texture: &glium::texture::Texture2d,
+    uv: UvRect,
+    tint: Vec4
+) -> Result<(), MenuError> {
+    let (x, y) = (rect.top_left.p[0], rect.top_left.p[1]);
+    let (right, bottom) = (x + rect.width, y + rect.height);
+    let quad = [
+        TexturedVertex { p: [ x, y ], uv: [ uv.min[0], uv.min[1] ] },
+        TexturedVertex { p: [ right, y ], uv: [ uv.max[0], uv.min[1] ] },
+        TexturedVertex { p: [ right, bottom ], uv: [ uv.max[0], uv.max[1] ] },
+        TexturedVertex { p: [ x, y ], uv: [ uv.min[0], uv.min[1] ] },
+        TexturedVertex { p: [ right, bottom ], uv: [ uv.max[0], uv.max[1] ] },
+        TexturedVertex { p: [ x, bottom ], uv: [ uv.min[0], uv.max[1] ] },
+    ];
+
+    let behavior = glium::uniforms::SamplerBehavior {
+        magnify_filter: glium::uniforms::MagnifySamplerFilter::Linear,
+        minify_filter: glium::uniforms::MinifySamplerFilter::Linear,
+        .. Default::default()
+    };
+    let uniforms = uniform! {
+        screen_size: [menu.window_size.0, menu.window_size.1],
+        offset: [menu.offset.0, menu.offset.1],
+        tex: glium::uniforms::Sampler(texture, behavior),
+        tint: faded(menu, tint).v
+    };
+
+    let vertex_buffer = glium::VertexBuffer::new(&menu.display, &quad)?;
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+
+    frame.draw(
+        &vertex_buffer,
+        &indices,
+        &menu.programs.textured,
+        &uniforms,
+        &blend_params()
+    )?;
+    menu.stats.count_draw_call();
+    Ok(())
+}
+
+// draws a triangle list with `fill`, gradients are laid out over `bounds`
+pub fn draw_filled_triangles(
+    menu: &Menu,
+    frame: &mut Frame,
+    triangles: &[Vertex],
+    bounds: &Rect,
+    fill: &fill::Fill
+) -> Result<(), MenuError> {
+    // size of the texture the gradient is baked into
+    const LOOKUP_SIZE: usize = 256;
+
+    let (start, end, radius) = match fill {
+        fill::Fill::Solid(color) => return draw_triangles(menu, frame, triangles, *color),
+        fill::Fill::Linear { angle, .. } => {
+            let (start, end) = fill::Fill::linear_points(*angle, bounds);
+            (start, end, 0.0f32)
+        },
+        fill::Fill::Radial { center, radius, .. } => {
+            let (center, radius) = fill::Fill::radial_points(*center, *radius, bounds);
+            (center, center, radius.max(0.0001))
+        },
+    };
+    if triangles.is_empty() {
+        return Ok(())
+    }
+
+    let key = fill.stops_key();
+    let mut gradients = menu.programs.gradients.borrow_mut();
+    if gradients.len() >= MAX_CACHED_GRADIENTS && !gradients.contains_key(&key) {
+        gradients.clear();
+    }
+    let lookup = match gradients.entry(key) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => {
+            let table = glium::texture::RawImage2d::from_raw_rgba(fill.lookup_table(LOOKUP_SIZE), (LOOKUP_SIZE as u32, 1));
+            entry.insert(glium::texture::Texture2d::new(&menu.display, table)?)
+        },
+    };
+    let behavior = glium::uniforms::SamplerBehavior {
+        wrap_function: (
+            glium::uniforms::SamplerWrapFunction::Clamp,
+            glium::uniforms::SamplerWrapFunction::Clamp,
+            glium::uniforms::SamplerWrapFunction::Clamp,
+        ),
+        magnify_filter: glium::uniforms::MagnifySamplerFilter::Linear,
+        minify_filter: glium::uniforms::MinifySamplerFilter::Linear,
+        .. Default::default()
+    };
+
+    let uniforms = uniform! {
+        screen_size: [menu.window_size.0, menu.window_size.1],
+        offset: [menu.offset.0, menu.offset.1],
+        start: start,
+        end: end,
+        radius: radius,
+        lookup: glium::uniforms::Sampler(&*lookup, behavior),
+        alpha: menu.opacity
+    };
+
+    let vertex_buffer = glium::VertexBuffer::new(&menu.display, triangles)?;
+    let indices = glium::index::NoIndices(glium::index::PrimitiveType::TrianglesList);
+
+    frame.draw(
+        &vertex_buffer,
+        &indices,
+        &menu.programs.gradient,
+        &uniforms,
+        &blend_params()
+    )?;
+    menu.stats.count_draw_call();
+    Ok(())
+}
+
+pub fn fill_rounded_rect_with(menu: &Menu, frame: &mut Frame, rect: &Rect, radii: [f32; 4], fill: &fill::Fill) -> Result<(), MenuError> {
+    draw_filled_triangles(menu, frame, &rounded_rect(rect, radii), rect, fill)
+}
+
+pub fn fill_rounded_rect(menu: &Menu, frame: &mut Frame, rect: &Rect, radii: [f32; 4], color: Vec4) -> Result<(), MenuError> {
+    draw_triangles(menu, frame, &rounded_rect(rect, radii), color)
+}
+
+pub fn stroke_rounded_rect(menu: &Menu, frame: &mut Frame, rect: &Rect, radii: [f32; 4], thickness: f32, color: Vec4) -> Result<(), MenuError> {
+    draw_triangles(menu, frame, &rounded_rect_border(rect, radii, thickness), color)
+}
+
+pub fn draw_drop_shadow(menu: &Menu, frame: &mut Frame, rect: &Rect, radius: f32, shadow: &Shadow) -> Result<(), MenuError> {
+    draw_colored_triangles(menu, frame, &drop_shadow(rect, radius, shadow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect::new(Vertex { p: [ x, y ] }, width, height)
+    }
+
+    fn inside(rect: &Rect, point: &Vertex) -> bool {
+        let epsilon = 1e-4;
+        point.p[0] >= rect.top_left.p[0] - epsilon && point.p[0] <= rect.top_left.p[0] + rect.width + epsilon
+            && point.p[1] >= rect.top_left.p[1] - epsilon && point.p[1] <= rect.top_left.p[1] + rect.height + epsilon
+    }
+
+    #[test]
+    fn clamps_corner_segments() {
+        assert_eq!(corner_segments(0.0), 1);
+        assert_eq!(corner_segments(8.0), 6);
+        assert_eq!(corner_segments(1000.0), 24);
+    }
+
+    #[test]
+    fn fills_rounded_rects() {
+        let bounds = rect(10.0, 20.0, 100.0, 40.0);
+        let triangles = rounded_rect(&bounds, [8.0; 4]);
+        // a fan with one triangle per point of the outline, 4 corners of 6 segments
+        assert_eq!(triangles.len(), 4 * 7 * 3);
+        assert!(triangles.iter().all(|point| inside(&bounds, point)));
+
+        let border = rounded_rect_border(&bounds, [8.0; 4], 2.0);
+        // a band of two triangles per point, its outer radius of 9 takes 7 segments a corner
+        assert_eq!(border.len(), 4 * 8 * 6);
+        assert!(border.iter().all(|point| inside(&bounds.grow(1.0), point)));
+    }
+
+    #[test]
+    fn clamps_radii_to_half_the_smaller_side() {
+        let bounds = rect(0.0, 0.0, 20.0, 10.0);
+        let path = rounded_rect_path(&bounds, [50.0; 4], 4);
+        assert!(path.iter().all(|point| inside(&bounds, point)));
+        // the top left corner starts on the left edge, one clamped radius down
+        assert!((path[0].p[0] - 0.0).abs() < 1e-4 && (path[0].p[1] - 5.0).abs() < 1e-4);
+        // and ends on the top edge, one radius in
+        assert!((path[4].p[0] - 5.0).abs() < 1e-4 && path[4].p[1].abs() < 1e-4);
+
+        let square = rounded_rect_path(&bounds, [0.0, -3.0, 0.0, 0.0], 2);
+        assert!(square.iter().all(|point| {
+            (point.p[0] == 0.0 || (point.p[0] - 20.0).abs() < 1e-4) && (point.p[1] == 0.0 || (point.p[1] - 10.0).abs() < 1e-4)
+        }));
+    }
+
+    fn points(points: &[(f32, f32)]) -> Vec<Vertex> {
+        points.iter().map(|point| Vertex { p: [ point.0, point.1 ] }).collect()
+    }
+
+    fn sharp(thickness: f32) -> StrokeStyle {
+        StrokeStyle { feather: 0.0, .. StrokeStyle::new(thickness) }
+    }
+
+    #[test]
+    fn strokes_segments() {
+        let white = Vec4::new(1.0, 1.0, 1.0, 1.0);
+        let line = points(&[ (0.0, 0.0), (10.0, 0.0) ]);
+
+        let triangles = stroke_polyline(&line, &sharp(2.0), white);
+        assert_eq!(triangles.len(), 6);
+        assert!(triangles.iter().all(|vertex| vertex.p[1].abs() <= 1.0 + 1e-4 && vertex.c[3] == 1.0));
+
+        // the feathered edges are two more quads, fading out to nothing
+        let feathered = stroke_polyline(&line, &StrokeStyle::new(2.0), white);
+        assert_eq!(feathered.len(), 18);
+        assert!(feathered.iter().any(|vertex| vertex.c[3] == 0.0));
+        assert!(feathered.iter().all(|vertex| vertex.p[1].abs() <= 1.5 + 1e-4));
+
+        // square caps reach half the thickness past the ends, round ones add points around them
+        let square = stroke_polyline(&line, &StrokeStyle { cap: LineCap::Square, .. sharp(2.0) }, white);
+        assert!(square.iter().any(|vertex| (vertex.p[0] + 1.0).abs() < 1e-4));
+        let round = stroke_polyline(&line, &StrokeStyle { cap: LineCap::Round, .. sharp(2.0) }, white);
+        assert!(round.len() > 6);
+    }
+
+    #[test]
+    fn skips_degenerate_strokes() {
+        let white = Vec4::new(1.0, 1.0, 1.0, 1.0);
+        assert!(stroke_polyline(&points(&[ (1.0, 1.0), (1.0, 1.0) ]), &sharp(2.0), white).is_empty());
+        assert!(stroke_polyline(&points(&[ (0.0, 0.0), (10.0, 0.0) ]), &sharp(0.0), white).is_empty());
+
+        // repeated points are dropped instead of producing a direction of NaN
+        let repeated = stroke_polyline(&points(&[ (0.0, 0.0), (0.0, 0.0), (10.0, 0.0) ]), &sharp(2.0), white);
+        assert_eq!(repeated.len(), 6);
+        assert!(repeated.iter().all(|vertex| vertex.p[0].is_finite() && vertex.p[1].is_finite()));
+    }
+
+    #[test]
+    fn joins_corners() {
+        let white = Vec4::new(1.0, 1.0, 1.0, 1.0);
+        let square = points(&[ (0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0) ]);
+
+        // a closed square has a miter at every corner and comes back to where it started
+        let closed = stroke_polyline(&square, &StrokeStyle { closed: true, .. sharp(2.0) }, white);
+        assert_eq!(closed.len(), 4 * 6);
+        assert!(closed.iter().any(|vertex| (vertex.p[0] + 1.0).abs() < 1e-4 && (vertex.p[1] + 1.0).abs() < 1e-4));
+
+        // past the miter limit the outer corner is cut off with a bevel
+        let corner = points(&[ (0.0, 0.0), (10.0, 0.0), (10.0, 10.0) ]);
+        let miter_point = |vertex: &ColorVertex| (vertex.p[0] - 11.0).abs() < 1e-4 && (vertex.p[1] + 1.0).abs() < 1e-4;
+        let mitered = stroke_polyline(&corner, &sharp(2.0), white);
+        let beveled = stroke_polyline(&corner, &StrokeStyle { miter_limit: 1.0, .. sharp(2.0) }, white);
+        assert_eq!(mitered.len(), 2 * 6);
+        assert_eq!(beveled.len(), 3 * 6);
+        assert!(mitered.iter().any(miter_point));
+        assert!(!beveled.iter().any(miter_point));
+    }
+
+    #[test]
+    fn fades_thin_strokes() {
+        let line = points(&[ (0.0, 0.0), (10.0, 0.0) ]);
+        let triangles = stroke_polyline(&line, &StrokeStyle::new(0.5), Vec4::new(1.0, 1.0, 1.0, 1.0));
+        let solid = triangles.iter().map(|vertex| vertex.c[3]).fold(0.0, f32::max);
+        assert!((solid - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn trims_polylines() {
+        let path = points(&[ (0.0, 0.0), (10.0, 0.0), (10.0, 10.0) ]);
+        let half = trim_polyline(&path, 0.5);
+        assert_eq!(half.len(), 2);
+        assert!((half[1].p[0] - 10.0).abs() < 1e-4 && half[1].p[1].abs() < 1e-4);
+
+        let quarter = trim_polyline(&path, 0.25);
+        assert!((quarter[1].p[0] - 5.0).abs() < 1e-4);
+        assert_eq!(trim_polyline(&path, 0.0).len(), 1);
+        assert_eq!(trim_polyline(&path, 2.0).len(), 3);
+    }
+
+    fn triangle_area(triangles: &[Vertex]) -> f32 {
+        triangles.chunks_exact(3).map(|triangle| {
+            let (a, b, c) = (triangle[0].p, triangle[1].p, triangle[2].p);
+            ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() / 2.0
+        }).sum()
+    }
+
+    #[test]
+    fn triangulates_polygons() {
+        let square = points(&[ (0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0) ]);
+        let triangles = triangulate_polygon(&square);
+        assert_eq!(triangles.len(), 2 * 3);
+        assert!((triangle_area(&triangles) - 100.0).abs() < 1e-3);
+
+        // the notch of an L can only be covered exactly if no ear reaches across it
+        let l_shape = points(&[ (0.0, 0.0), (10.0, 0.0), (10.0, 4.0), (4.0, 4.0), (4.0, 10.0), (0.0, 10.0) ]);
+        let triangles = triangulate_polygon(&l_shape);
+        assert_eq!(triangles.len(), 4 * 3);
+        assert!((triangle_area(&triangles) - 64.0).abs() < 1e-3);
+
+        // either winding works
+        let reversed = l_shape.iter().rev().copied().collect::<Vec<_>>();
+        let triangles = triangulate_polygon(&reversed);
+        assert_eq!(triangles.len(), 4 * 3);
+        assert!((triangle_area(&triangles) - 64.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn triangulates_degenerate_polygons() {
+        assert!(triangulate_polygon(&points(&[ (0.0, 0.0), (1.0, 1.0) ])).is_empty());
+
+        // points on a line still come out as triangles, just without any area
+        let line = triangulate_polygon(&points(&[ (0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0) ]));
+        assert_eq!(line.len(), 2 * 3);
+        assert!(triangle_area(&line) < 1e-6);
+    }
+
+    #[test]
+    fn fills_ellipses_and_pies() {
+        let center = Vertex { p: [ 0.0, 0.0 ] };
+        let ellipse = ellipse(center, (10.0, 5.0));
+        assert_eq!(ellipse.len(), circle_segments(10.0) * 3);
+        assert!(ellipse.iter().all(|point| (point.p[0] / 10.0).powi(2) + (point.p[1] / 5.0).powi(2) <= 1.0 + 1e-4));
+
+        let quarter = pie(center, 10.0, 0.0, PI / 2.0);
+        assert_eq!(quarter.len(), sweep_segments(10.0, PI / 2.0) * 3);
+        assert!(quarter.iter().all(|point| point.p[0] >= -1e-4 && point.p[1] >= -1e-4));
+        assert!((triangle_area(&quarter) - PI * 25.0).abs() < 2.0);
+    }
+
+    #[test]
+    fn skips_degenerate_outlines() {
+        assert!(fill_convex(&[ Vertex { p: [ 0.0, 0.0 ] }, Vertex { p: [ 1.0, 0.0 ] } ]).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use std::time::{ Duration, Instant };
+
+use winit::application::ApplicationHandler;
+use winit::event::WindowEvent;
+use winit::event_loop::{ ActiveEventLoop, ControlFlow, EventLoop };
+use winit::window::{ Window, WindowId };
+
+use crate::{ Menu, error::MenuError };
+
+// how often the update callback still runs while `Menu::idle_when_hidden` has drawing paused
+const IDLE_INTERVAL: Duration = Duration::from_millis(100);
+
+// how often `Menu::run` draws a frame. vsync is set when the overlay is built, with
+// `OverlayBuilder::with_vsync`, and on by default
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum FramePacing {
+    // as fast as the buffer swap allows, which is once per monitor refresh with vsync
+    #[default]
+    Unlimited,
+    // at most this many frames per second
+    Fps(f32),
+    // the refresh rate of the monitor the overlay, and so the target window, is on
+    MatchRefreshRate,
+}
+
+// handed to the update callback every frame
+pub struct FrameContext<'a> {
+    pub window: &'a Window,
+    // seconds the last frame took
+    pub delta_time: f32,
+    // frames drawn so far
+    pub frame: u64,
+    exit: bool,
+}
+
+impl FrameContext<'_> {
+    // closes the overlay after this frame
+    pub fn exit(&mut self) {
+        self.exit = true;
+    }
+}
+
+struct MenuApp<F> {
+    menu: Menu,
+    window: Window,
+    update: F,
+    next_frame: Instant,
+    frame: u64,
+    idle: bool,
+    // the draw error that closed the loop, returned from `run`
+    error: Option<MenuError>,
+}
+
+impl<F> MenuApp<F>
+where
+    F: FnMut(&mut Menu, &mut FrameContext)
+{
+    // None draws again as soon as the last frame is done
+    fn frame_interval(&self) -> Option<Duration> {
+        match self.menu.pacing {
+            FramePacing::Unlimited => None,
+            FramePacing::Fps(fps) if fps > 0.0 => Some(Duration::from_secs_f32(1.0 / fps)),
+            FramePacing::Fps(_) => None,
+            FramePacing::MatchRefreshRate => self.window
+                .current_monitor()
+                .and_then(|monitor| monitor.refresh_rate_millihertz())
+                .filter(|millihertz| *millihertz > 0)
+                .map(|millihertz| Duration::from_secs_f64(1000.0 / millihertz as f64)),
+        }
+    }
+    fn draw_frame(&mut self, event_loop: &ActiveEventLoop) {
+        let mut context = FrameContext {
+            window: &self.window,
+            delta_time: self.menu.delta_time,
+            frame: self.frame,
+            exit: false,
+        };
+        (self.update)(&mut self.menu, &mut context);
+        let exit = context.exit;
+
+        if self.menu.idle_when_hidden && !self.menu.needs_redraw() {
+            self.menu.skip_frame();
+        } else {
+            if let Err(error) = self.menu.try_draw_menu() {
+                self.error = Some(error);
+                event_loop.exit();
+                return
+            }
+            self.frame += 1;
+        }
+
+        if exit {
+            event_loop.exit();
+        }
+    }
+}
+
+impl<F> ApplicationHandler for MenuApp<F>
+where
+    F: FnMut(&mut Menu, &mut FrameContext)
+{
+    // the window already exists, it is created with the overlay
+    fn resumed(&mut self, _event_loop: &ActiveEventLoop) {}
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _window_id: WindowId, event: WindowEvent) {
+        self.menu.handle_event(&event);
+
+        match event {
+            WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::RedrawRequested => self.draw_frame(event_loop),
+            _ => (),
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        self.menu.poll_tracker();
+
+        let idle = self.menu.idle_when_hidden && !self.menu.needs_redraw();
+        if self.idle && !idle {
+            // input or a new drawing shouldn't wait for the rest of the idle interval
+            self.next_frame = Instant::now();
+        }
+        self.idle = idle;
+
+        let interval = if idle { Some(IDLE_INTERVAL) } else { self.frame_interval() };
+        match interval {
+            Some(interval) => {
+                let now = Instant::now();
+                if now >= self.next_frame {
+                    // when running behind, start counting from now instead of trying to catch up
+                    self.next_frame = (self.next_frame + interval).max(now);
+                    self.window.request_redraw();
+                }
+                event_loop.set_control_flow(ControlFlow::WaitUntil(self.next_frame));
+            },
+            None => {
+                self.window.request_redraw();
+                event_loop.set_control_flow(ControlFlow::Poll);
+            },
+        }
+    }
+}
+
+pub fn run<F>(menu: Menu, event_loop: EventLoop<()>, window: Window, update: F) -> Result<(), MenuError>
+where
+    F: FnMut(&mut Menu, &mut FrameContext)
+{
+    let mut app = MenuApp {
+        menu,
+        window,
+        update,
+        next_frame: Instant::now(),
+        frame: 0,
+        idle: false,
+        error: None,
+    };
+    event_loop.run_app(&mut app)?;
+    match app.error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
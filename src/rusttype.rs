@@ -12,7 +12,7 @@ Usage:
 # fn main() {
 # let display: glium::Display = unsafe { std::mem::uninitialized() };
 // The `TextSystem` contains the shaders and elements used for text display.
-let system = glium_text::TextSystem::new(&display);
+let system = glium_text::TextSystem::try_new(&display).unwrap();
 
 // Creating a `FontTexture`, which a regular `Texture` which contains the font.
 // Note that loading the systems fonts is not covered by this library.
@@ -24,14 +24,14 @@ let font = glium_text::FontTexture::new(
 ).unwrap();
 
 // Creating a `TextDisplay` which contains the elements required to draw a specific sentence.
-let text = glium_text::TextDisplay::new(&system, &font, "Hello world!");
+let text = glium_text::TextDisplay::new(&system, &font, "Hello world!").unwrap();
 
 // Finally, drawing the text is done like this:
 let matrix = [[1.0, 0.0, 0.0, 0.0],
               [0.0, 1.0, 0.0, 0.0],
               [0.0, 0.0, 1.0, 0.0],
               [0.0, 0.0, 0.0, 1.0]];
-glium_text::draw(&text, &system, &mut display.draw(), matrix, (1.0, 1.0, 0.0, 1.0));
+glium_text::draw(&text, &system, &mut display.draw(), matrix, (1.0, 1.0, 0.0, 1.0)).unwrap();
 # }
 ```
 
@@ -43,6 +43,21 @@ chained ranges with it like this:
 This will rasterize font with characters from "Basic Latin" and "Cyrillic"
 sets.  Here is a complete list of ranges with description.
 
+When the characters aren't known up front, `FontTexture::dynamic` creates a
+font whose glyphs are rasterized the first time a `TextDisplay` uses them.
+The glyph texture grows as it fills up and, once it reached its size limit,
+the least recently used glyphs make room for new ones.
+
+Text is laid out with the font's kerning pairs, and combining marks are placed
+over the character before them. Scripts that need contextual forms or
+ligatures, like Arabic or Devanagari, can be shaped with HarfBuzz rules by
+enabling the `shaping` feature and calling `FontTexture::set_shaping` on a
+dynamic font, or `FontRegistry::set_shaping` before loading it into a menu.
+
+`FontTexture::sdf` creates a dynamic font that stores signed distance fields
+instead of coverage. Its text stays sharp at any size and `draw_with_effects`
+can draw outlines, glows and drop shadows around it in the same draw call.
+
 *As of now, Rust doesn't provide a way to create inclusive ranges yet so thou
 shall add +1 to the outer bound.*
 
@@ -180,6 +195,7 @@ extern crate rusttype;
 use glium::*;
 
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::default::Default;
 use std::io::Read;
@@ -194,8 +210,25 @@ use glium::backend::Facade;
 
 /// Texture which contains the characters of the font.
 pub struct FontTexture {
-    texture: glium::texture::Texture2d,
-    character_infos: HashMap<char, CharacterInfos>,
+    font: rusttype::Font<'static>,
+    font_size: u32,
+    glyphs: Glyphs,
+    // the font file again, the shaper parses it on its own
+    #[cfg(feature = "shaping")]
+    font_data: Rc<Vec<u8>>,
+    #[cfg(feature = "shaping")]
+    shaping: bool,
+}
+
+// where a `FontTexture` keeps its glyphs
+enum Glyphs {
+    // every character rasterized once in `FontTexture::new`
+    Baked {
+        texture: glium::texture::Texture2d,
+        character_infos: HashMap<char, CharacterInfos>,
+    },
+    // characters rasterized on first use, see `FontTexture::dynamic`
+    Dynamic(RefCell<GlyphCache>),
 }
 
 ///
@@ -205,6 +238,18 @@ pub enum Error {
     NoGlyph(char),
     /// An Error that comes directly from Rusttype.
     RusttypeError(rusttype::Error),
+    /// The texture holding the glyphs could not be created.
+    Texture(glium::texture::TextureCreationError),
+    /// The font could not be read.
+    Io(std::io::Error),
+    /// The text shaders failed to compile or link.
+    Program(glium::program::ProgramChooserCreationError),
+    /// The vertices of a text could not be uploaded.
+    VertexBuffer(glium::vertex::BufferCreationError),
+    /// The indices of a text could not be uploaded.
+    IndexBuffer(glium::index::BufferCreationError),
+    /// Drawing a text was rejected.
+    Draw(glium::DrawError),
 }
 
 impl From<rusttype::Error> for Error {
@@ -213,18 +258,58 @@ impl From<rusttype::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<glium::DrawError> for Error {
+    fn from(error: glium::DrawError) -> Self {
+        Error::Draw(error)
+    }
+}
+
 /// Object that contains the elements shared by all `TextDisplay` objects.
 ///
 /// Required to create a `TextDisplay`.
 pub struct TextSystem {
     context: Rc<Context>,
     program: glium::Program,
+    // for fonts created with `FontTexture::sdf`
+    sdf_program: glium::Program,
+}
+
+/// Effects drawn around the text of signed distance field fonts, see
+/// `draw_with_effects`. Other fonts draw the text alone.
+///
+/// Widths and offsets are in EMs. They can't reach further than the distance
+/// field does, which is `FontTexture::sdf_spread` EMs around every glyph.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextEffects {
+    /// Color of the line around the glyphs.
+    pub outline_color: (f32, f32, f32, f32),
+    /// Width of the line around the glyphs, 0 draws none.
+    pub outline_width: f32,
+    /// Color the glow starts with at the outline and fades out from.
+    pub glow_color: (f32, f32, f32, f32),
+    /// How far the glow reaches past the outline, 0 draws none.
+    pub glow_width: f32,
+    /// Color of the shadow, a transparent color draws none.
+    pub shadow_color: (f32, f32, f32, f32),
+    /// How far the shadow is moved right and down.
+    pub shadow_offset: (f32, f32),
+    /// How far the edge of the shadow is blurred.
+    pub shadow_softness: f32,
 }
 
 /// Object that will allow you to draw a text.
 pub struct TextDisplay<F> where F: Deref<Target=FontTexture> {
     context: Rc<Context>,
     texture: F,
+    text: String,
+    // glyph cache generation the texture coordinates were taken from
+    generation: u64,
     vertex_buffer: Option<glium::VertexBuffer<VertexFormat>>,
     index_buffer: Option<glium::IndexBuffer<u16>>,
     total_text_width: f32,
@@ -254,6 +339,69 @@ struct CharacterInfos {
     right_padding: f32,
 }
 
+// a rasterized glyph, one byte of coverage per pixel
+struct Bitmap {
+    rows: i32,
+    width: i32,
+    buffer: Vec<u8>,
+}
+
+// size dynamic glyph textures start at, they double from there
+const INITIAL_CACHE_SIZE: u32 = 512;
+// size dynamic glyph textures stop growing at, unless told otherwise
+const DEFAULT_MAX_CACHE_SIZE: u32 = 4096;
+// a margin around each character to prevent artifacts
+const MARGIN: u32 = 2;
+// how far the distance fields of `FontTexture::sdf` reach around their glyph, in EMs
+const SDF_SPREAD: f32 = 0.25;
+
+// glyph texture of a dynamic `FontTexture`. every glyph gets a slot of the same size, so the
+// slot of an evicted glyph fits whichever glyph replaces it
+struct GlyphCache {
+    font: rusttype::Font<'static>,
+    font_size: u32,
+    context: Rc<Context>,
+    texture: glium::texture::Texture2d,
+    // the texture is square, this is its width in pixels
+    size: u32,
+    max_size: u32,
+    // size of one slot in pixels, margin included
+    slot_size: (u32, u32),
+    // pixels the distance field reaches around every glyph, 0 stores plain coverage
+    spread: u32,
+    // by glyph id, so shaped text can use glyphs no single character maps to
+    glyphs: HashMap<u16, CachedGlyph>,
+    free_slots: Vec<(u32, u32)>,
+    // bumped for every text laid out, glyphs are evicted by when they were last used in one
+    tick: u64,
+    // bumped whenever texture coordinates handed out before stop being valid
+    generation: u64,
+}
+
+struct CachedGlyph {
+    // column and row of the slot
+    slot: (u32, u32),
+    // with texture coordinates in pixels, see `normalize_infos`
+    infos: CharacterInfos,
+    last_used: u64,
+}
+
+// a glyph positioned by `FontTexture::layout`
+#[derive(Copy, Clone, Debug)]
+struct PlacedGlyph {
+    infos: CharacterInfos,
+    // left edge of the glyph in EMs from the start of the text
+    x: f32,
+    // EMs the glyph is raised above the base line
+    y: f32,
+}
+
+struct Layout {
+    glyphs: Vec<PlacedGlyph>,
+    // EMs from the start of the text to where the next character would go
+    width: f32,
+}
+
 struct TextureData {
     data: Vec<f32>,
     width: u32,
@@ -292,29 +440,512 @@ impl FontTexture {
     /// texture.  Complexity grows as `font_size**2 * characters_list.len()`.
     /// **Avoid rasterizing everything at once as it will be slow and end up in
     /// out of memory abort.**
-    pub fn new<R, F, I>(facade: &F, font: R, font_size: u32, characters_list: I)
+    pub fn new<R, F, I>(facade: &F, mut font: R, font_size: u32, characters_list: I)
                         -> Result<FontTexture, Error>
         where R: Read, F: Facade, I: IntoIterator<Item=char>
     {
 
         // building the freetype face object
-        let font: Vec<u8> = font.bytes().map(|c| c.unwrap()).collect();
+        let mut bytes = Vec::new();
+        font.read_to_end(&mut bytes)?;
+        let font = bytes;
 
-        let collection = ::rusttype::FontCollection::from_bytes(&font[..])?;
-        let font = collection.into_font().unwrap();
+        #[cfg(feature = "shaping")]
+        let font_data = Rc::new(font.clone());
+        let collection = ::rusttype::FontCollection::from_bytes(font)?;
+        let font = collection.into_font()?;
 
         // building the infos
         let (texture_data, chr_infos) =
             build_font_image(&font, characters_list.into_iter(), font_size)?;
 
         // we load the texture in the display
-        let texture = glium::texture::Texture2d::new(facade, &texture_data).unwrap();
+        let texture = glium::texture::Texture2d::new(facade, &texture_data).map_err(Error::Texture)?;
 
         Ok(FontTexture {
-            texture,
-            character_infos: chr_infos,
+            font,
+            font_size,
+            glyphs: Glyphs::Baked {
+                texture,
+                character_infos: chr_infos,
+            },
+            #[cfg(feature = "shaping")]
+            font_data,
+            #[cfg(feature = "shaping")]
+            shaping: false,
+        })
+    }
+
+    /// Creates a font texture that rasterizes characters the first time they are
+    /// displayed instead of up front, so any character the font has can be drawn.
+    ///
+    /// The glyph texture starts small and doubles whenever it is full, up to
+    /// 4096x4096 pixels. After that the least recently used glyphs are evicted.
+    pub fn dynamic<R, F>(facade: &F, font: R, font_size: u32) -> Result<FontTexture, Error>
+        where R: Read, F: Facade
+    {
+        FontTexture::dynamic_with_limit(facade, font, font_size, DEFAULT_MAX_CACHE_SIZE)
+    }
+
+    /// Like `dynamic`, with the largest width and height the glyph texture may grow to.
+    pub fn dynamic_with_limit<R, F>(facade: &F, font: R, font_size: u32, max_texture_size: u32)
+                                    -> Result<FontTexture, Error>
+        where R: Read, F: Facade
+    {
+        FontTexture::with_glyph_cache(facade, font, font_size, max_texture_size, 0)
+    }
+
+    /// Creates a dynamic font texture that stores a signed distance field of every
+    /// glyph instead of its coverage. The text stays sharp at sizes far from
+    /// `font_size`, so a much smaller `font_size` is enough, and `draw_with_effects`
+    /// can outline, glow and shadow it.
+    pub fn sdf<R, F>(facade: &F, font: R, font_size: u32) -> Result<FontTexture, Error>
+        where R: Read, F: Facade
+    {
+        FontTexture::sdf_with_limit(facade, font, font_size, DEFAULT_MAX_CACHE_SIZE)
+    }
+
+    /// Like `sdf`, with the largest width and height the glyph texture may grow to.
+    pub fn sdf_with_limit<R, F>(facade: &F, font: R, font_size: u32, max_texture_size: u32)
+                                -> Result<FontTexture, Error>
+        where R: Read, F: Facade
+    {
+        let spread = ((font_size as f32 * SDF_SPREAD).ceil() as u32).max(1);
+        FontTexture::with_glyph_cache(facade, font, font_size, max_texture_size, spread)
+    }
+
+    fn with_glyph_cache<R, F>(facade: &F, mut font: R, font_size: u32, max_texture_size: u32, spread: u32)
+                              -> Result<FontTexture, Error>
+        where R: Read, F: Facade
+    {
+        let mut bytes = Vec::new();
+        font.read_to_end(&mut bytes)?;
+        let font = bytes;
+
+        #[cfg(feature = "shaping")]
+        let font_data = Rc::new(font.clone());
+        let collection = ::rusttype::FontCollection::from_bytes(font)?;
+        let font = collection.into_font()?;
+
+        // `Font` shares its bytes, the clone is cheap
+        let cache = GlyphCache::new(facade.get_context().clone(), font.clone(), font_size, max_texture_size, spread)?;
+
+        Ok(FontTexture {
+            font,
+            font_size,
+            glyphs: Glyphs::Dynamic(RefCell::new(cache)),
+            #[cfg(feature = "shaping")]
+            font_data,
+            #[cfg(feature = "shaping")]
+            shaping: false,
+        })
+    }
+
+    /// Lays text out with the HarfBuzz shaping rules of its script instead of one
+    /// glyph per character, for ligatures, contextual forms and mark positioning.
+    /// Only dynamic fonts can be shaped, pre-baked fonts ignore this.
+    #[cfg(feature = "shaping")]
+    pub fn set_shaping(&mut self, shaping: bool) {
+        self.shaping = shaping;
+    }
+
+    /// Returns true for fonts created with `dynamic` or `sdf`.
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self.glyphs, Glyphs::Dynamic(_))
+    }
+
+    /// Returns true for fonts created with `sdf`.
+    pub fn is_sdf(&self) -> bool {
+        self.spread() > 0
+    }
+
+    /// Returns how far in EMs the distance field reaches around every glyph, which
+    /// limits the effects `draw_with_effects` can draw. 0 for fonts that aren't `sdf`.
+    pub fn sdf_spread(&self) -> f32 {
+        self.spread() as f32 / self.font_size as f32
+    }
+
+    fn spread(&self) -> u32 {
+        match &self.glyphs {
+            Glyphs::Baked { .. } => 0,
+            Glyphs::Dynamic(cache) => cache.borrow().spread,
+        }
+    }
+
+    /// Rasterizes characters of a dynamic font ahead of time, so the frame that
+    /// first displays them doesn't have to. Does nothing for pre-baked fonts.
+    pub fn preload<I>(&self, characters: I) where I: IntoIterator<Item=char> {
+        if let Glyphs::Dynamic(cache) = &self.glyphs {
+            let glyphs = characters.into_iter()
+                .map(|character| self.font.glyph(character).id().0)
+                .collect::<Vec<_>>();
+            cache.borrow_mut().lookup(&glyphs);
+        }
+    }
+
+    /// Returns how far the font reaches above the base line, in EMs.
+    pub fn ascent(&self) -> f32 {
+        let v_metrics = self.font.v_metrics_unscaled();
+        v_metrics.ascent / (v_metrics.ascent - v_metrics.descent)
+    }
+
+    /// Returns how far the font reaches below the base line, in EMs. Together with
+    /// `ascent` this is one EM.
+    pub fn descent(&self) -> f32 {
+        let v_metrics = self.font.v_metrics_unscaled();
+        -v_metrics.descent / (v_metrics.ascent - v_metrics.descent)
+    }
+
+    /// Returns the gap the font asks for between the descent of one line and the
+    /// ascent of the next, in EMs.
+    pub fn line_gap(&self) -> f32 {
+        let v_metrics = self.font.v_metrics_unscaled();
+        v_metrics.line_gap / (v_metrics.ascent - v_metrics.descent)
+    }
+
+    /// Returns true if the font can show `character`. Pre-baked fonts only know the
+    /// characters they were created with.
+    pub fn has_glyph(&self, character: char) -> bool {
+        match &self.glyphs {
+            Glyphs::Baked { character_infos, .. } => character_infos.contains_key(&character),
+            Glyphs::Dynamic(_) => self.font.glyph(character).id().0 != 0,
+        }
+    }
+
+    /// Returns the width in EMs `text` would have in a `TextDisplay`, without
+    /// building one.
+    pub fn text_width(&self, text: &str) -> f32 {
+        match &self.glyphs {
+            Glyphs::Baked { .. } => self.layout(text).width,
+            // from the font's metrics, measuring never rasterizes or evicts glyphs
+            Glyphs::Dynamic(_) => self.metrics_width(text),
+        }
+    }
+
+    // the width `layout` gives `text`, from the advances and kerning of the font alone
+    fn metrics_width(&self, text: &str) -> f32 {
+        #[cfg(feature = "shaping")]
+        if self.shaping {
+            if let Some((shaped, units_per_em)) = self.shape(text) {
+                return shaped.glyph_positions().iter()
+                    .map(|position| position.x_advance as f32 / units_per_em)
+                    .sum()
+            }
+        }
+
+        let scale = ::rusttype::Scale::uniform(self.font_size as f32);
+        let mut width = 0.0;
+        let mut base: Option<char> = None;
+        for character in text.chars() {
+            // marks over a base don't move the pen, see `layout`
+            if base.is_some() && is_combining_mark(character) {
+                continue
+            }
+            if let Some(previous) = base {
+                width += self.font.pair_kerning(scale, previous, character);
+            }
+            width += self.font.glyph(character).scaled(scale).h_metrics().advance_width;
+            base = Some(character);
+        }
+        width / self.font_size as f32
+    }
+
+    // positions every glyph of `text`, kerned, with combining marks over their base
+    fn layout(&self, text: &str) -> Layout {
+        #[cfg(feature = "shaping")]
+        if self.shaping {
+            if let Glyphs::Dynamic(cache) = &self.glyphs {
+                if let Some(layout) = self.shaped_layout(cache, text) {
+                    return layout
+                }
+            }
+        }
+
+        let scale = ::rusttype::Scale::uniform(self.font_size as f32);
+        let em_pixels = self.font_size as f32;
+
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut pen = 0.0;
+        // the last character that wasn't a mark, where it started and how far it advanced
+        let mut base: Option<(char, f32, f32)> = None;
+
+        for (character, infos) in text.chars().zip(self.lookup(text)) {
+            let infos = match infos {
+                Some(infos) => infos,
+                None => continue,
+            };
+            let advance = infos.left_padding + infos.size.0 + infos.right_padding;
+
+            if let (true, Some((_, start, base_advance))) = (is_combining_mark(character), base) {
+                // marks made for combining have no advance and sit over the pen already,
+                // spacing ones are centered over their base
+                let x = if advance > 0.0 {
+                    start + (base_advance - infos.size.0) / 2.0
+                } else {
+                    pen + infos.left_padding
+                };
+                glyphs.push(PlacedGlyph { infos, x, y: 0.0 });
+                continue
+            }
+
+            if let Some((previous, _, _)) = base {
+                pen += self.font.pair_kerning(scale, previous, character) / em_pixels;
+            }
+            glyphs.push(PlacedGlyph { infos, x: pen + infos.left_padding, y: 0.0 });
+            base = Some((character, pen, advance));
+            pen += advance;
+        }
+
+        Layout {
+            glyphs,
+            width: pen,
+        }
+    }
+
+    // None when the shaper can't read the font, the text is laid out per character then
+    #[cfg(feature = "shaping")]
+    fn shaped_layout(&self, cache: &RefCell<GlyphCache>, text: &str) -> Option<Layout> {
+        let (shaped, units_per_em) = self.shape(text)?;
+
+        let ids = shaped.glyph_infos().iter().map(|info| info.glyph_id as u16).collect::<Vec<_>>();
+        let infos = cache.borrow_mut().lookup(&ids);
+
+        let mut glyphs = Vec::with_capacity(ids.len());
+        let mut pen = 0.0;
+        for (infos, position) in infos.into_iter().zip(shaped.glyph_positions()) {
+            if let Some(infos) = infos {
+                glyphs.push(PlacedGlyph {
+                    infos,
+                    x: pen + position.x_offset as f32 / units_per_em + infos.left_padding,
+                    y: position.y_offset as f32 / units_per_em,
+                });
+            }
+            pen += position.x_advance as f32 / units_per_em;
+        }
+
+        Some(Layout {
+            glyphs,
+            width: pen,
         })
     }
+
+    // the shaped glyphs of `text` and the font units in an EM, None when the shaper can't
+    // read the font
+    #[cfg(feature = "shaping")]
+    fn shape(&self, text: &str) -> Option<(rustybuzz::GlyphBuffer, f32)> {
+        let face = rustybuzz::Face::from_slice(&self.font_data, 0)?;
+
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.guess_segment_properties();
+        let shaped = rustybuzz::shape(&face, &[], buffer);
+
+        // an EM here is the font's height from descent to ascent, see `rasterize_glyph`
+        let v_metrics = self.font.v_metrics_unscaled();
+        Some((shaped, v_metrics.ascent - v_metrics.descent))
+    }
+
+    // the infos of every character of `text` in texture units and EMs, None for the ones
+    // the font can't show
+    fn lookup(&self, text: &str) -> Vec<Option<CharacterInfos>> {
+        match &self.glyphs {
+            Glyphs::Baked { character_infos, .. } => {
+                text.chars().map(|character| character_infos.get(&character).copied()).collect()
+            },
+            Glyphs::Dynamic(cache) => {
+                let glyphs = text.chars().map(|character| self.font.glyph(character).id().0).collect::<Vec<_>>();
+                cache.borrow_mut().lookup(&glyphs)
+            },
+        }
+    }
+
+    // changes whenever infos returned by `lookup` before are no longer valid
+    fn generation(&self) -> u64 {
+        match &self.glyphs {
+            Glyphs::Baked { .. } => 0,
+            Glyphs::Dynamic(cache) => cache.borrow().generation,
+        }
+    }
+}
+
+impl GlyphCache {
+    fn new(context: Rc<Context>, font: rusttype::Font<'static>, font_size: u32, max_size: u32, spread: u32)
+           -> Result<GlyphCache, Error>
+    {
+        // every glyph of the font fits between its ascent and descent, with its distance field around it
+        let v_metrics = font.v_metrics(::rusttype::Scale::uniform(font_size as f32));
+        let line_height = ((v_metrics.ascent - v_metrics.descent).ceil() as u32).max(1) + spread * 2;
+        let slot_size = (line_height + MARGIN, line_height + MARGIN);
+
+        let max_size = get_nearest_po2(max_size.max(slot_size.0));
+        let size = get_nearest_po2(INITIAL_CACHE_SIZE.max(slot_size.0)).min(max_size);
+        let texture = empty_glyph_texture(&context, size)?;
+
+        let mut cache = GlyphCache {
+            font,
+            font_size,
+            context,
+            texture,
+            size,
+            max_size,
+            slot_size,
+            spread,
+            glyphs: HashMap::new(),
+            free_slots: Vec::new(),
+            tick: 0,
+            generation: 0,
+        };
+        cache.add_free_slots((0, 0));
+        Ok(cache)
+    }
+
+    // the infos of glyph ids, all of them used by the same text
+    fn lookup(&mut self, glyphs: &[u16]) -> Vec<Option<CharacterInfos>> {
+        self.tick += 1;
+        let cached = glyphs.iter().map(|id| self.glyph(*id)).collect::<Vec<_>>();
+
+        // growing or evicting moved glyphs that were looked up earlier in the same text
+        let texture_size = (self.size as f32, self.size as f32);
+        let em_pixels = self.font_size as f32;
+        cached.into_iter().map(|id| {
+            let glyph = self.glyphs.get(&id?)?;
+            Some(normalize_infos(glyph.infos, texture_size, em_pixels))
+        }).collect()
+    }
+
+    // makes sure glyph `id` is in the texture, returns it again for `lookup`
+    fn glyph(&mut self, id: u16) -> Option<u16> {
+        if let Some(glyph) = self.glyphs.get_mut(&id) {
+            glyph.last_used = self.tick;
+            return Some(id)
+        }
+
+        let slot = self.allocate_slot()?;
+        let (bitmap, mut infos) = self.rasterize(id);
+        infos.tex_coords = ((slot.0 * self.slot_size.0) as f32, (slot.1 * self.slot_size.1) as f32);
+        let (width, rows) = self.write_slot(slot, &bitmap);
+
+        // a glyph wider or taller than the slot is drawn cut off instead of squeezed, and still
+        // advances as far
+        let written = (width as f32, rows as f32);
+        infos.right_padding += infos.size.0 - written.0;
+        infos.size = written;
+        infos.tex_size = written;
+
+        self.glyphs.insert(id, CachedGlyph {
+            slot,
+            infos,
+            last_used: self.tick,
+        });
+        Some(id)
+    }
+
+    fn rasterize(&self, id: u16) -> (Bitmap, CharacterInfos) {
+        let (bitmap, infos) = rasterize_glyph(self.font.glyph(::rusttype::GlyphId(id)), self.font_size);
+        if self.spread == 0 {
+            return (bitmap, infos)
+        }
+        signed_distance_field(&bitmap, infos, self.spread)
+    }
+
+    fn allocate_slot(&mut self) -> Option<(u32, u32)> {
+        if let Some(slot) = self.free_slots.pop() {
+            return Some(slot)
+        }
+        if self.size < self.max_size && self.grow().is_ok() {
+            return self.free_slots.pop()
+        }
+
+        // glyphs of the text being laid out right now are never evicted, if those alone fill
+        // the texture the remaining characters are left out
+        let tick = self.tick;
+        let id = self.glyphs.iter()
+            .filter(|(_, glyph)| glyph.last_used < tick)
+            .min_by_key(|(_, glyph)| glyph.last_used)
+            .map(|(id, _)| *id)?;
+        let evicted = self.glyphs.remove(&id)?;
+        self.generation += 1;
+        Some(evicted.slot)
+    }
+
+    // doubles the texture, existing glyphs keep their slots
+    fn grow(&mut self) -> Result<(), Error> {
+        let size = (self.size * 2).min(self.max_size);
+        self.texture = empty_glyph_texture(&self.context, size)?;
+
+        let old_slots = (self.size / self.slot_size.0, self.size / self.slot_size.1);
+        self.size = size;
+        self.generation += 1;
+
+        let glyphs = self.glyphs.iter().map(|(id, glyph)| (*id, glyph.slot)).collect::<Vec<_>>();
+        for (id, slot) in glyphs {
+            let (bitmap, _) = self.rasterize(id);
+            self.write_slot(slot, &bitmap);
+        }
+        self.add_free_slots(old_slots);
+        Ok(())
+    }
+
+    // every slot of the texture outside of the first `used` columns and rows
+    fn add_free_slots(&mut self, used: (u32, u32)) {
+        let columns = self.size / self.slot_size.0;
+        let rows = self.size / self.slot_size.1;
+        // popped from the back, so the top left slots are handed out first
+        for row in (0 .. rows).rev() {
+            for column in (0 .. columns).rev() {
+                if column >= used.0 || row >= used.1 {
+                    self.free_slots.push((column, row));
+                }
+            }
+        }
+    }
+
+    // replaces the whole slot, so nothing of the glyph that was there before is left over.
+    // returns the width and rows of the bitmap that fit
+    fn write_slot(&self, slot: (u32, u32), bitmap: &Bitmap) -> (u32, u32) {
+        let (slot_width, slot_height) = self.slot_size;
+        let mut data = vec![0.0f32; (slot_width * slot_height) as usize];
+
+        // the margin stays empty, anything that doesn't fit is cut off
+        let width = (bitmap.width as u32).min(slot_width - MARGIN);
+        let rows = (bitmap.rows as u32).min(slot_height - MARGIN);
+        for y in 0 .. rows {
+            for x in 0 .. width {
+                let value = bitmap.buffer[(y * bitmap.width as u32 + x) as usize];
+                data[(y * slot_width + x) as usize] = f32::from(value) / f32::from(u8::MAX);
+            }
+        }
+
+        let rect = glium::Rect {
+            left: slot.0 * slot_width,
+            bottom: slot.1 * slot_height,
+            width: slot_width,
+            height: slot_height,
+        };
+        self.texture.write(rect, glium::texture::RawImage2d {
+            data: Cow::Owned(data),
+            width: slot_width,
+            height: slot_height,
+            format: glium::texture::ClientFormat::F32,
+        });
+        (width, rows)
+    }
+}
+
+// a single channel texture with nothing in it yet
+fn empty_glyph_texture(context: &Rc<Context>, size: u32) -> Result<glium::texture::Texture2d, Error> {
+    let blank = glium::texture::RawImage2d {
+        data: Cow::Owned(vec![0.0f32; (size * size) as usize]),
+        width: size,
+        height: size,
+        format: glium::texture::ClientFormat::F32,
+    };
+    glium::texture::Texture2d::with_format(
+        context,
+        blank,
+        glium::texture::UncompressedFloatFormat::U8,
+        glium::texture::MipmapsOption::NoMipmap
+    ).map_err(Error::Texture)
 }
 
 /*impl glium::uniforms::AsUniformValue for FontTexture {
@@ -325,8 +956,15 @@ impl FontTexture {
 
 impl TextSystem {
     /// Builds a new text system that must be used to build `TextDisplay` objects.
+    ///
+    /// Panics when the shaders don't compile, see `try_new`.
     pub fn new<F>(facade: &F) -> TextSystem where F: Facade {
-        TextSystem {
+        TextSystem::try_new(facade).unwrap()
+    }
+
+    /// Like `new`, returning an error when the driver fails to compile the shaders.
+    pub fn try_new<F>(facade: &F) -> Result<TextSystem, Error> where F: Facade {
+        Ok(TextSystem {
             context: facade.get_context().clone(),
             program: program!(facade,
                 140 => {
@@ -392,17 +1030,160 @@ impl TextSystem {
                     "
                 },
 
-            ).unwrap()
-        }
+            ).map_err(Error::Program)?,
+            sdf_program: program!(facade,
+                140 => {
+                    vertex: "
+                        #version 140
+
+                        uniform mat4 matrix;
+                        in vec2 position;
+                        in vec2 tex_coords;
+
+                        out vec2 v_tex_coords;
+
+                        void main() {
+                            gl_Position = matrix * vec4(position, 0.0, 1.0);
+                            v_tex_coords = tex_coords;
+                        }
+                    ",
+                    fragment: "
+                        #version 140
+                        in vec2 v_tex_coords;
+                        out vec4 f_color;
+                        uniform vec4 color;
+                        uniform sampler2D tex;
+                        // EMs between the texture values 0 and 1, 0.5 is the outline of the glyph
+                        uniform float spread;
+                        // texture units per EM
+                        uniform vec2 em_size;
+                        uniform vec4 outline_color;
+                        uniform float outline_width;
+                        uniform vec4 glow_color;
+                        uniform float glow_width;
+                        uniform vec4 shadow_color;
+                        uniform vec2 shadow_offset;
+                        uniform float shadow_softness;
+
+                        // EMs from the outline of the glyph, positive inside it
+                        float distance_at(vec2 coords) {
+                            return (texture(tex, coords).r - 0.5) * spread;
+                        }
+
+                        // `top` drawn over `bottom`, both premultiplied
+                        vec4 over(vec4 top, vec4 bottom) {
+                            return top + bottom * (1.0 - top.a);
+                        }
+
+                        vec4 layer(vec4 color, float coverage) {
+                            float alpha = color.a * clamp(coverage, 0.0, 1.0);
+                            return vec4(color.rgb * alpha, alpha);
+                        }
+
+                        void main() {
+                            float d = distance_at(v_tex_coords);
+                            // half a pixel in EMs, so edges stay one pixel soft at any size
+                            float aa = max(fwidth(d), 0.0001) * 0.5;
+                            float outer = d + outline_width;
+
+                            float shadow = distance_at(v_tex_coords - shadow_offset * em_size) + outline_width;
+                            vec4 c = layer(shadow_color, smoothstep(-shadow_softness - aa, shadow_softness + aa, shadow));
+                            if (glow_width > 0.0) {
+                                c = over(layer(glow_color, 1.0 - smoothstep(0.0, glow_width, -outer)), c);
+                            }
+                            if (outline_width > 0.0) {
+                                c = over(layer(outline_color, smoothstep(-aa, aa, outer)), c);
+                            }
+                            c = over(layer(color, smoothstep(-aa, aa, d)), c);
+
+                            if (c.a <= 0.01) {
+                                discard;
+                            } else {
+                                f_color = vec4(c.rgb / c.a, c.a);
+                            }
+                        }
+                    "
+                },
+
+                110 => {
+                    vertex: "
+                        #version 110
+
+                        attribute vec2 position;
+                        attribute vec2 tex_coords;
+                        varying vec2 v_tex_coords;
+                        uniform mat4 matrix;
+
+                        void main() {
+                            gl_Position = matrix * vec4(position.x, position.y, 0.0, 1.0);
+                            v_tex_coords = tex_coords;
+                        }
+                    ",
+                    fragment: "
+                        #version 110
+
+                        varying vec2 v_tex_coords;
+                        uniform vec4 color;
+                        uniform sampler2D tex;
+                        uniform float spread;
+                        uniform vec2 em_size;
+                        uniform vec4 outline_color;
+                        uniform float outline_width;
+                        uniform vec4 glow_color;
+                        uniform float glow_width;
+                        uniform vec4 shadow_color;
+                        uniform vec2 shadow_offset;
+                        uniform float shadow_softness;
+
+                        float distance_at(vec2 coords) {
+                            return (texture2D(tex, coords).r - 0.5) * spread;
+                        }
+
+                        vec4 over(vec4 top, vec4 bottom) {
+                            return top + bottom * (1.0 - top.a);
+                        }
+
+                        vec4 layer(vec4 color, float coverage) {
+                            float alpha = color.a * clamp(coverage, 0.0, 1.0);
+                            return vec4(color.rgb * alpha, alpha);
+                        }
+
+                        void main() {
+                            float d = distance_at(v_tex_coords);
+                            float aa = max(fwidth(d), 0.0001) * 0.5;
+                            float outer = d + outline_width;
+
+                            float shadow = distance_at(v_tex_coords - shadow_offset * em_size) + outline_width;
+                            vec4 c = layer(shadow_color, smoothstep(-shadow_softness - aa, shadow_softness + aa, shadow));
+                            if (glow_width > 0.0) {
+                                c = over(layer(glow_color, 1.0 - smoothstep(0.0, glow_width, -outer)), c);
+                            }
+                            if (outline_width > 0.0) {
+                                c = over(layer(outline_color, smoothstep(-aa, aa, outer)), c);
+                            }
+                            c = over(layer(color, smoothstep(-aa, aa, d)), c);
+
+                            if (c.a <= 0.01) {
+                                discard;
+                            }
+                            gl_FragColor = vec4(c.rgb / c.a, c.a);
+                        }
+                    "
+                },
+
+            ).map_err(Error::Program)?,
+        })
     }
 }
 
 impl<F> TextDisplay<F> where F: Deref<Target=FontTexture> {
     /// Builds a new text display that allows you to draw text.
-    pub fn new(system: &TextSystem, texture: F, text: &str) -> TextDisplay<F> {
+    pub fn new(system: &TextSystem, texture: F, text: &str) -> Result<TextDisplay<F>, Error> {
         let mut text_display = TextDisplay {
             context: system.context.clone(),
             texture,
+            text: String::new(),
+            generation: 0,
             vertex_buffer: None,
             index_buffer: None,
             total_text_width: 0.0,
@@ -410,9 +1191,8 @@ impl<F> TextDisplay<F> where F: Deref<Target=FontTexture> {
             is_empty: true,
         };
 
-        text_display.set_text(text);
-
-        text_display
+        text_display.set_text(text)?;
+        Ok(text_display)
     }
 
     /// Returns the width in GL units of the text.
@@ -425,28 +1205,49 @@ impl<F> TextDisplay<F> where F: Deref<Target=FontTexture> {
         self.text_height
     }
 
-    /// Modifies the text on this display.
-    pub fn set_text(&mut self, text: &str) {
+    /// Returns the font texture the text is drawn with.
+    pub fn get_texture(&self) -> &FontTexture {
+        &self.texture
+    }
+
+    /// Returns true when the glyphs of a dynamic font were moved or evicted since this
+    /// text was laid out. Stale text is laid out again every time it is drawn, until
+    /// `refresh` or `set_text` is called.
+    pub fn is_stale(&self) -> bool {
+        self.texture.generation() != self.generation
+    }
+
+    /// Lays the current text out again, see `is_stale`.
+    pub fn refresh(&mut self) -> Result<(), Error> {
+        let text = std::mem::take(&mut self.text);
+        self.set_text(&text)
+    }
+
+    /// Modifies the text on this display. On error the display is left empty.
+    pub fn set_text(&mut self, text: &str) -> Result<(), Error> {
         self.is_empty = true;
         self.total_text_width = 0.0;
         self.vertex_buffer = None;
         self.index_buffer = None;
+        self.text = text.to_string();
+        self.generation = self.texture.generation();
 
         // returning if no text
         if text.is_empty() {
-            return;
+            return Ok(());
         }
 
         // these arrays will contain the vertex buffer and index buffer data
         let mut vertex_buffer_data = Vec::with_capacity(text.len() * 4 * 4);
         let mut index_buffer_data = Vec::with_capacity(text.len() * 6);
 
-        // iterating over the characters of the string
-        for character in text.chars() {
-            let infos = match self.texture.character_infos.get(&character) {
-                Some(infos) => infos,
-                None => continue,
-            };
+        // dynamic fonts rasterize missing characters here, which may change the generation
+        let layout = self.texture.layout(text);
+        self.generation = self.texture.generation();
+
+        // iterating over the placed glyphs of the string
+        for glyph in layout.glyphs.iter() {
+            let infos = &glyph.infos;
 
             self.is_empty = false;
 
@@ -461,14 +1262,11 @@ impl<F> TextDisplay<F> where F: Deref<Target=FontTexture> {
                 index_buffer_data.push(first_vertex_offset + 3);
             }
 
-            //
-            self.total_text_width += infos.left_padding;
-
             // calculating coords
-            let left_coord = self.total_text_width;
+            let left_coord = glyph.x;
             let right_coord = left_coord + infos.size.0;
-            let top_coord = infos.height_over_line;
-            let bottom_coord = infos.height_over_line - infos.size.1;
+            let top_coord = infos.height_over_line + glyph.y;
+            let bottom_coord = top_coord - infos.size.1;
 
             // top-left vertex
             vertex_buffer_data.push(VertexFormat {
@@ -497,24 +1295,38 @@ impl<F> TextDisplay<F> where F: Deref<Target=FontTexture> {
                 ],
             });
 
-            // going to next char
-            self.total_text_width = right_coord + infos.right_padding;
-
             if top_coord > self.text_height {
                 self.text_height = top_coord;
             }
         }
 
-        if !vertex_buffer_data.len() != 0 {
+        // the same width `FontTexture::text_width` measures
+        self.total_text_width = layout.width;
+
+        if !vertex_buffer_data.is_empty() {
             // building the vertex buffer
-            self.vertex_buffer = Some(glium::VertexBuffer::new(&self.context,
-                                                               &vertex_buffer_data).unwrap());
+            let vertex_buffer = glium::VertexBuffer::new(&self.context, &vertex_buffer_data)
+                .map_err(Error::VertexBuffer);
 
             // building the index buffer
-            self.index_buffer = Some(glium::IndexBuffer::new(&self.context,
-                                     glium::index::PrimitiveType::TrianglesList,
-                                     &index_buffer_data).unwrap());
+            let index_buffer = glium::IndexBuffer::new(&self.context,
+                                                       glium::index::PrimitiveType::TrianglesList,
+                                                       &index_buffer_data)
+                .map_err(Error::IndexBuffer);
+
+            // nothing is drawn from half a text
+            match (vertex_buffer, index_buffer) {
+                (Ok(vertex_buffer), Ok(index_buffer)) => {
+                    self.vertex_buffer = Some(vertex_buffer);
+                    self.index_buffer = Some(index_buffer);
+                },
+                (Err(error), _) | (_, Err(error)) => {
+                    self.is_empty = true;
+                    return Err(error)
+                },
+            }
         }
+        Ok(())
     }
 }
 
@@ -533,7 +1345,24 @@ pub fn draw<F, S: ?Sized, M>(
     target: &mut S,
     matrix: M,
     color: (f32, f32, f32, f32)
-) -> Result<(), glium::DrawError>
+) -> Result<(), Error>
+    where S: glium::Surface,
+          M: Into<[[f32; 4]; 4]>,
+          F: Deref<Target=FontTexture>
+{
+    draw_with_effects(text, system, target, matrix, color, &TextEffects::default())
+}
+
+/// Like `draw`, with outlines, glows and shadows around the text of fonts created
+/// with `FontTexture::sdf`. Other fonts ignore the effects.
+pub fn draw_with_effects<F, S: ?Sized, M>(
+    text: &TextDisplay<F>,
+    system: &TextSystem,
+    target: &mut S,
+    matrix: M,
+    color: (f32, f32, f32, f32),
+    effects: &TextEffects
+) -> Result<(), Error>
     where S: glium::Surface,
           M: Into<[[f32; 4]; 4]>,
           F: Deref<Target=FontTexture>
@@ -564,7 +1393,7 @@ pub fn draw<F, S: ?Sized, M>(
             .. Default::default()
         }
     };
-    draw_with_params(text, system, target, matrix, color, behavior, &params)
+    draw_glyphs(text, system, target, matrix.into(), color, effects, behavior, &params)
 }
 
 /// More advanced variant of `draw` which also takes sampler behavior and draw
@@ -577,12 +1406,33 @@ pub fn draw_with_params<F, S: ?Sized, M>(
     color: (f32, f32, f32, f32),
     sampler_behavior: glium::uniforms::SamplerBehavior,
     parameters: &DrawParameters
-) -> Result<(), glium::DrawError>
+) -> Result<(), Error>
     where S: glium::Surface,
           M: Into<[[f32; 4]; 4]>,
           F: Deref<Target=FontTexture>
 {
-    let matrix = matrix.into();
+    draw_glyphs(text, system, target, matrix.into(), color, &TextEffects::default(), sampler_behavior, parameters)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_glyphs<F, S: ?Sized>(
+    text: &TextDisplay<F>,
+    system: &TextSystem,
+    target: &mut S,
+    matrix: [[f32; 4]; 4],
+    color: (f32, f32, f32, f32),
+    effects: &TextEffects,
+    sampler_behavior: glium::uniforms::SamplerBehavior,
+    parameters: &DrawParameters
+) -> Result<(), Error>
+    where S: glium::Surface,
+          F: Deref<Target=FontTexture>
+{
+    // laid out against glyphs that have moved since, a fresh copy gets the current ones
+    if text.is_stale() {
+        let fresh = TextDisplay::new(system, &*text.texture, &text.text)?;
+        return draw_glyphs(&fresh, system, target, matrix, color, effects, sampler_behavior, parameters)
+    }
 
     let &TextDisplay {
         ref vertex_buffer,
@@ -601,13 +1451,44 @@ pub fn draw_with_params<F, S: ?Sized, M>(
     let vertex_buffer = vertex_buffer.as_ref().unwrap();
     let index_buffer = index_buffer.as_ref().unwrap();
 
+    let cache = match &texture.glyphs {
+        Glyphs::Baked { texture, .. } => {
+            let uniforms = uniform! {
+                matrix: matrix,
+                color: color,
+                tex: glium::uniforms::Sampler(texture, sampler_behavior)
+            };
+            return Ok(target.draw(vertex_buffer, index_buffer, &system.program, &uniforms, &parameters)?)
+        },
+        Glyphs::Dynamic(cache) => cache.borrow(),
+    };
+
+    if cache.spread == 0 {
+        let uniforms = uniform! {
+            matrix: matrix,
+            color: color,
+            tex: glium::uniforms::Sampler(&cache.texture, sampler_behavior)
+        };
+        return Ok(target.draw(vertex_buffer, index_buffer, &system.program, &uniforms, &parameters)?)
+    }
+
+    let rgba = |color: (f32, f32, f32, f32)| [color.0, color.1, color.2, color.3];
+    let em_pixels = cache.font_size as f32;
     let uniforms = uniform! {
         matrix: matrix,
         color: color,
-        tex: glium::uniforms::Sampler(&texture.texture, sampler_behavior)
+        tex: glium::uniforms::Sampler(&cache.texture, sampler_behavior),
+        spread: cache.spread as f32 * 2.0 / em_pixels,
+        em_size: [em_pixels / cache.size as f32, em_pixels / cache.size as f32],
+        outline_color: rgba(effects.outline_color),
+        outline_width: effects.outline_width.max(0.0),
+        glow_color: rgba(effects.glow_color),
+        glow_width: effects.glow_width.max(0.0),
+        shadow_color: rgba(effects.shadow_color),
+        shadow_offset: [effects.shadow_offset.0, effects.shadow_offset.1],
+        shadow_softness: effects.shadow_softness.max(0.0),
     };
-
-    target.draw(vertex_buffer, index_buffer, &system.program, &uniforms, &parameters)
+    Ok(target.draw(vertex_buffer, index_buffer, &system.sdf_program, &uniforms, &parameters)?)
 }
 
 fn build_font_image<I>(font: &rusttype::Font, characters_list: I, font_size: u32)
@@ -616,12 +1497,6 @@ fn build_font_image<I>(font: &rusttype::Font, characters_list: I, font_size: u32
 {
     use std::iter;
 
-    // a margin around each character to prevent artifacts
-    const MARGIN: u32 = 2;
-
-    // glyph size for characters not presented in font.
-    let invalid_character_width = font_size / 2;
-
     let size_estimation = characters_list.size_hint().1.unwrap_or(0);
 
     // this variable will store the texture data
@@ -646,42 +1521,7 @@ fn build_font_image<I>(font: &rusttype::Font, characters_list: I, font_size: u32
     // now looping through the list of characters, filling the texture and returning the informations
     let em_pixels = font_size as f32;
     let characters_infos = characters_list.map(|character| {
-        struct Bitmap {
-            rows   : i32,
-            width  : i32,
-            buffer : Vec<u8>
-        }
-        // loading wanted glyph in the font face
-        // hope scale will set the right pixel size
-        let scaled_glyph = font.glyph(character)
-            .scaled(::rusttype::Scale {x : font_size as f32, y : font_size as f32 });
-        let h_metrics = scaled_glyph.h_metrics();
-        let glyph = scaled_glyph
-            .positioned(::rusttype::Point {x : 0.0, y : 0.0 });
-
-        let bb = glyph.pixel_bounding_box();
-        // if no bounding box - we suppose that its invalid character but want it to be draw as empty quad
-        let bb = if let Some(bb) = bb {
-            bb
-        } else {
-            Rect {
-                min: Point {x: 0, y: 0},
-                max: Point {x: invalid_character_width as i32, y: 0}
-            }
-        };
-
-        let mut buffer = vec![0; (bb.height() * bb.width()) as usize];
-
-        glyph.draw(|x, y, v| {
-            let x = x;
-            let y = y;
-            buffer[(y * bb.width() as u32 + x) as usize] = (v * 255.0) as u8;
-        });
-        let bitmap : Bitmap = Bitmap {
-            rows   : bb.height(),
-            width  : bb.width(),
-            buffer
-        };
+        let (bitmap, mut infos) = rasterize_glyph(font.glyph(character), font_size);
 
         // adding a left margin before our character to prevent artifacts
         cursor_offset.0 += MARGIN;
@@ -707,7 +1547,6 @@ fn build_font_image<I>(font: &rusttype::Font, characters_list: I, font_size: u32
         if bitmap.rows >= 1 {
             let destination = &mut texture_data[(cursor_offset.0 + cursor_offset.1 * texture_width) as usize ..];
             let source = &bitmap.buffer;
-            //ylet source = std::slice::from_raw_parts(source, destination.len());
 
             for y in 0 .. bitmap.rows as u32 {
                 let source = &source[(y * bitmap.width as u32) as usize ..];
@@ -726,19 +1565,10 @@ fn build_font_image<I>(font: &rusttype::Font, characters_list: I, font_size: u32
             debug_assert!(cursor_offset.0 <= texture_width);
         }
 
-        // filling infos about that character
-        // tex_size and tex_coords are in pixels for the moment ; they will be divided
+        // tex_coords are in pixels for the moment ; they will be divided
         // by the texture dimensions later
-        Ok((character, CharacterInfos {
-            tex_size: (bitmap.width as f32, bitmap.rows as f32),
-            tex_coords: (offset_x_before_copy as f32, cursor_offset.1 as f32),
-            size: (bitmap.width as f32, bitmap.rows as f32),
-            left_padding: h_metrics.left_side_bearing as f32,
-            right_padding: (h_metrics.advance_width
-                            - bitmap.width as f32
-                            - h_metrics.left_side_bearing as f32) as f32 / 64.0,
-            height_over_line: -bb.min.y as f32,
-        }))
+        infos.tex_coords = (offset_x_before_copy as f32, cursor_offset.1 as f32);
+        Ok((character, infos))
     }).collect::<Result<Vec<_>, Error>>()?;
 
     // adding blank lines at the end until the height of the texture is a power of two
@@ -753,17 +1583,8 @@ fn build_font_image<I>(font: &rusttype::Font, characters_list: I, font_size: u32
     assert!((texture_data.len() as u32 % texture_width) == 0);
     let texture_height = (texture_data.len() as u32 / texture_width) as f32;
     let float_texture_width = texture_width as f32;
-    let mut characters_infos = characters_infos.into_iter().map(|mut chr| {
-        chr.1.tex_size.0 /= float_texture_width;
-        chr.1.tex_size.1 /= texture_height;
-        chr.1.tex_coords.0 /= float_texture_width;
-        chr.1.tex_coords.1 /= texture_height;
-        chr.1.size.0 /= em_pixels;
-        chr.1.size.1 /= em_pixels;
-        chr.1.left_padding /= em_pixels;
-        chr.1.right_padding /= em_pixels;
-        chr.1.height_over_line /= em_pixels;
-        chr
+    let mut characters_infos = characters_infos.into_iter().map(|(character, infos)| {
+        (character, normalize_infos(infos, (float_texture_width, texture_height), em_pixels))
     }).collect::<HashMap<_, _>>();
 
     // this HashMap will not be used mutably any more and it makes sense to
@@ -778,6 +1599,185 @@ fn build_font_image<I>(font: &rusttype::Font, characters_list: I, font_size: u32
     }, characters_infos))
 }
 
+// rasterizes a single glyph, the infos are in pixels and still lack the texture coordinates
+fn rasterize_glyph(glyph: rusttype::Glyph, font_size: u32) -> (Bitmap, CharacterInfos) {
+    // glyph size for characters not presented in font.
+    let invalid_character_width = font_size / 2;
+
+    // hope scale will set the right pixel size
+    let scaled_glyph = glyph
+        .scaled(::rusttype::Scale {x : font_size as f32, y : font_size as f32 });
+    let h_metrics = scaled_glyph.h_metrics();
+    let glyph = scaled_glyph
+        .positioned(::rusttype::Point {x : 0.0, y : 0.0 });
+
+    let bb = glyph.pixel_bounding_box();
+    // if no bounding box - we suppose that its invalid character but want it to be draw as empty quad
+    let bb = if let Some(bb) = bb {
+        bb
+    } else {
+        Rect {
+            min: Point {x: 0, y: 0},
+            max: Point {x: invalid_character_width as i32, y: 0}
+        }
+    };
+
+    let mut buffer = vec![0; (bb.height() * bb.width()) as usize];
+
+    glyph.draw(|x, y, v| {
+        buffer[(y * bb.width() as u32 + x) as usize] = (v * 255.0) as u8;
+    });
+    let bitmap = Bitmap {
+        rows   : bb.height(),
+        width  : bb.width(),
+        buffer
+    };
+
+    let infos = CharacterInfos {
+        tex_size: (bitmap.width as f32, bitmap.rows as f32),
+        tex_coords: (0.0, 0.0),
+        size: (bitmap.width as f32, bitmap.rows as f32),
+        left_padding: h_metrics.left_side_bearing as f32,
+        right_padding: h_metrics.advance_width
+                       - bitmap.width as f32
+                       - h_metrics.left_side_bearing,
+        height_over_line: -bb.min.y as f32,
+    };
+    (bitmap, infos)
+}
+
+// turns the coverage of a rasterized glyph into distances from its outline, `spread` pixels
+// wide on every side. 0.5 is the outline, values go up inside the glyph and down outside.
+// the infos grow with the bitmap, the glyph advances as far as before
+fn signed_distance_field(bitmap: &Bitmap, mut infos: CharacterInfos, spread: u32) -> (Bitmap, CharacterInfos) {
+    let padding = spread as i32;
+    let width = bitmap.width + padding * 2;
+    let rows = bitmap.rows + padding * 2;
+
+    let coverage = |x: i32, y: i32| {
+        let (x, y) = (x - padding, y - padding);
+        if x >= 0 && y >= 0 && x < bitmap.width && y < bitmap.rows {
+            f32::from(bitmap.buffer[(y * bitmap.width + x) as usize]) / f32::from(u8::MAX)
+        } else {
+            0.0
+        }
+    };
+    let inside = |x: i32, y: i32| coverage(x, y) >= 0.5;
+    // pixels the outline goes through, partly covered or next to a pixel on its other side
+    let on_edge = |x: i32, y: i32| {
+        let value = coverage(x, y);
+        value > 0.0 && value < 1.0
+            || [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().any(|(dx, dy)| inside(x + dx, y + dy) != inside(x, y))
+    };
+    let nearest = nearest_seeds(width, rows, on_edge);
+
+    let mut buffer = Vec::with_capacity((width * rows) as usize);
+    for y in 0 .. rows {
+        for x in 0 .. width {
+            let sign = if inside(x, y) { 1.0 } else { -1.0 };
+            let distance = match nearest[(y * width + x) as usize] {
+                // an edge pixel is as far from the outline as its coverage is from half covered,
+                // which keeps the contours smooth between pixels
+                Some((dx, dy)) => {
+                    let length = ((dx * dx + dy * dy) as f32).sqrt();
+                    coverage(x + dx, y + dy) - 0.5 + sign * length
+                },
+                // an empty glyph
+                None => sign * spread as f32,
+            };
+            let value = 0.5 + distance / (spread as f32 * 2.0);
+            buffer.push((value.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+    }
+
+    let spread = spread as f32;
+    infos.tex_size = (infos.tex_size.0 + spread * 2.0, infos.tex_size.1 + spread * 2.0);
+    infos.size = (infos.size.0 + spread * 2.0, infos.size.1 + spread * 2.0);
+    infos.left_padding -= spread;
+    infos.right_padding -= spread;
+    infos.height_over_line += spread;
+    (Bitmap { rows, width, buffer }, infos)
+}
+
+// offset from every pixel to the nearest one `seed` is true for, None without any seed, with
+// the two passes of 8SSEDT. pixels are compared by the offset to their nearest seed, so it stays
+// linear in the size of the glyph however far the field reaches
+fn nearest_seeds<S>(width: i32, rows: i32, seed: S) -> Vec<Option<(i32, i32)>> where S: Fn(i32, i32) -> bool {
+    // further than any glyph is wide, still far from overflowing when squared
+    const FAR: (i32, i32) = (9999, 9999);
+    let length = |offset: (i32, i32)| offset.0 * offset.0 + offset.1 * offset.1;
+
+    let mut offsets = Vec::with_capacity((width * rows) as usize);
+    for y in 0 .. rows {
+        for x in 0 .. width {
+            offsets.push(if seed(x, y) { (0, 0) } else { FAR });
+        }
+    }
+
+    let compare = |offsets: &mut Vec<(i32, i32)>, x: i32, y: i32, step: (i32, i32)| {
+        let (other_x, other_y) = (x + step.0, y + step.1);
+        if other_x < 0 || other_y < 0 || other_x >= width || other_y >= rows {
+            return
+        }
+        let other = offsets[(other_y * width + other_x) as usize];
+        if other == FAR {
+            return
+        }
+        let candidate = (other.0 + step.0, other.1 + step.1);
+        let index = (y * width + x) as usize;
+        if length(candidate) < length(offsets[index]) {
+            offsets[index] = candidate;
+        }
+    };
+
+    for y in 0 .. rows {
+        for x in 0 .. width {
+            for step in [(-1, 0), (0, -1), (-1, -1), (1, -1)] {
+                compare(&mut offsets, x, y, step);
+            }
+        }
+        for x in (0 .. width).rev() {
+            compare(&mut offsets, x, y, (1, 0));
+        }
+    }
+    for y in (0 .. rows).rev() {
+        for x in (0 .. width).rev() {
+            for step in [(1, 0), (0, 1), (-1, 1), (1, 1)] {
+                compare(&mut offsets, x, y, step);
+            }
+        }
+        for x in 0 .. width {
+            compare(&mut offsets, x, y, (-1, 0));
+        }
+    }
+
+    offsets.into_iter().map(|offset| (offset != FAR).then_some(offset)).collect()
+}
+
+// marks that attach to the character before them, from the combining diacritical mark blocks
+fn is_combining_mark(character: char) -> bool {
+    matches!(character,
+        '\u{0300}' ..= '\u{036F}'
+        | '\u{1AB0}' ..= '\u{1AFF}'
+        | '\u{1DC0}' ..= '\u{1DFF}'
+        | '\u{20D0}' ..= '\u{20FF}'
+        | '\u{FE20}' ..= '\u{FE2F}')
+}
+
+// converts infos from pixels to texture units and EMs
+fn normalize_infos(mut infos: CharacterInfos, texture_size: (f32, f32), em_pixels: f32) -> CharacterInfos {
+    infos.tex_size.0 /= texture_size.0;
+    infos.tex_size.1 /= texture_size.1;
+    infos.tex_coords.0 /= texture_size.0;
+    infos.tex_coords.1 /= texture_size.1;
+    infos.size.0 /= em_pixels;
+    infos.size.1 /= em_pixels;
+    infos.left_padding /= em_pixels;
+    infos.right_padding /= em_pixels;
+    infos.height_over_line /= em_pixels;
+    infos
+}
+
 /// Function that will calculate the nearest power of two.
 fn get_nearest_po2(mut x: u32) -> u32 {
     assert!(x > 0);
@@ -789,3 +1789,89 @@ fn get_nearest_po2(mut x: u32) -> u32 {
     x = x | (x >> 16);
     x + 1
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn infos(width: i32, rows: i32) -> CharacterInfos {
+        CharacterInfos {
+            tex_coords: (0.0, 0.0),
+            tex_size: (width as f32, rows as f32),
+            size: (width as f32, rows as f32),
+            height_over_line: rows as f32,
+            left_padding: 1.0,
+            right_padding: 1.0,
+        }
+    }
+
+    // the left `covered` columns full, the next one half covered, the rest empty
+    fn half_plane(width: i32, rows: i32, covered: i32) -> Bitmap {
+        let mut buffer = Vec::new();
+        for _ in 0 .. rows {
+            for x in 0 .. width {
+                buffer.push(if x < covered { 255 } else if x == covered { 128 } else { 0 });
+            }
+        }
+        Bitmap { rows, width, buffer }
+    }
+
+    #[test]
+    fn finds_nearest_seeds() {
+        let nearest = nearest_seeds(5, 3, |x, y| (x, y) == (1, 1));
+        let at = |x: usize, y: usize| nearest[y * 5 + x];
+        assert_eq!(at(0, 0), Some((1, 1)));
+        assert_eq!(at(1, 1), Some((0, 0)));
+        assert_eq!(at(4, 1), Some((-3, 0)));
+        assert_eq!(at(3, 2), Some((-2, -1)));
+
+        let nearest = nearest_seeds(5, 3, |x, _| x == 0 || x == 4);
+        assert_eq!(nearest[5 + 1], Some((-1, 0)));
+        assert_eq!(nearest[5 + 3], Some((1, 0)));
+    }
+
+    #[test]
+    fn finds_no_seeds_in_empty_fields() {
+        assert!(nearest_seeds(4, 4, |_, _| false).iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn puts_the_outline_at_half_value() {
+        let spread = 3;
+        let (field, _) = signed_distance_field(&half_plane(6, 4, 3), infos(6, 4), spread);
+        let row = (spread as i32 + 2) * field.width;
+        let edge = field.buffer[(row + spread as i32 + 3) as usize];
+        assert!((126 ..= 130).contains(&edge), "{}", edge);
+    }
+
+    #[test]
+    fn falls_off_away_from_the_outline() {
+        let spread = 3;
+        let (field, _) = signed_distance_field(&half_plane(6, 4, 3), infos(6, 4), spread);
+        let row = ((spread as i32 + 2) * field.width) as usize;
+        let values = &field.buffer[row .. row + field.width as usize];
+        // the glyph covers columns `spread` to `spread + 3`, half covered at `spread + 3`
+        let edge = spread as usize + 3;
+        assert!(values[.. spread as usize + 1].windows(2).all(|pair| pair[0] < pair[1]), "{:?}", values);
+        assert!(values[edge - 1 ..].windows(2).all(|pair| pair[0] >= pair[1]), "{:?}", values);
+        assert!(values[edge - 1] > 128 && values[edge + 1] < 128, "{:?}", values);
+        assert_eq!(values[values.len() - 1], 0);
+    }
+
+    #[test]
+    fn maps_empty_glyphs_to_zero() {
+        let empty = Bitmap { rows: 3, width: 2, buffer: vec![0; 6] };
+        let (field, _) = signed_distance_field(&empty, infos(2, 3), 2);
+        assert_eq!((field.width, field.rows), (6, 7));
+        assert!(field.buffer.iter().all(|value| *value == 0));
+    }
+
+    #[test]
+    fn grows_infos_with_the_spread() {
+        let (_, grown) = signed_distance_field(&half_plane(6, 4, 3), infos(6, 4), 2);
+        assert_eq!(grown.size, (10.0, 8.0));
+        assert_eq!(grown.tex_size, (10.0, 8.0));
+        assert_eq!((grown.left_padding, grown.right_padding), (-1.0, -1.0));
+        assert_eq!(grown.height_over_line, 6.0);
+    }
+}
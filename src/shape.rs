@@ -0,0 +1,107 @@
+use std::f32::consts::PI;
+
+use crate::{ Menu, Vertex, Vec4, Draw, MenuOptions, Options, primitives, error::MenuError };
+
+use glium::Frame;
+
+// angles are in radians, clockwise on screen starting from the positive x axis
+pub enum ShapeKind {
+    Circle { center: Vertex, radius: f32 },
+    Ellipse { center: Vertex, radius: (f32, f32) },
+    // outlined arcs are left open, filled ones are drawn as a pie slice
+    Arc { center: Vertex, radius: f32, start: f32, sweep: f32 },
+    Pie { center: Vertex, radius: f32, start: f32, sweep: f32 },
+    // convex or concave, but not self intersecting
+    Polygon(Vec<Vertex>),
+}
+
+pub struct Shape {
+    options: MenuOptions,
+    pub kind: ShapeKind,
+    color: Vec4,
+    // None fills the shape, otherwise the outline thickness
+    thickness: Option<f32>,
+}
+
+impl Shape {
+    pub fn filled(options: MenuOptions, kind: ShapeKind, color: Vec4) -> Self {
+        Self {
+            options,
+            kind,
+            color,
+            thickness: None,
+        }
+    }
+    pub fn outlined(options: MenuOptions, kind: ShapeKind, color: Vec4, thickness: f32) -> Self {
+        Self {
+            options,
+            kind,
+            color,
+            thickness: Some(thickness),
+        }
+    }
+    fn fill(&self) -> Vec<Vertex> {
+        match &self.kind {
+            ShapeKind::Circle { center, radius } => primitives::ellipse(*center, (*radius, *radius)),
+            ShapeKind::Ellipse { center, radius } => primitives::ellipse(*center, *radius),
+            ShapeKind::Arc { center, radius, start, sweep }
+            | ShapeKind::Pie { center, radius, start, sweep } => primitives::pie(*center, *radius, *start, *sweep),
+            ShapeKind::Polygon(points) => primitives::triangulate_polygon(points),
+        }
+    }
+    fn outline(&self, thickness: f32) -> Vec<primitives::ColorVertex> {
+        let mut style = primitives::StrokeStyle::new(thickness);
+
+        let path = match &self.kind {
+            ShapeKind::Circle { center, radius } => {
+                style.closed = true;
+                ellipse_outline(*center, (*radius, *radius))
+            },
+            ShapeKind::Ellipse { center, radius } => {
+                style.closed = true;
+                ellipse_outline(*center, *radius)
+            },
+            ShapeKind::Arc { center, radius, start, sweep } => {
+                primitives::ellipse_path(*center, (*radius, *radius), *start, *sweep, primitives::sweep_segments(*radius, *sweep))
+            },
+            ShapeKind::Pie { center, radius, start, sweep } => {
+                style.closed = true;
+                let mut path = vec![ *center ];
+                path.extend(primitives::ellipse_path(*center, (*radius, *radius), *start, *sweep, primitives::sweep_segments(*radius, *sweep)));
+                path
+            },
+            ShapeKind::Polygon(points) => {
+                style.closed = true;
+                points.clone()
+            },
+        };
+
+        primitives::stroke_polyline(&path, &style, self.color)
+    }
+}
+
+fn ellipse_outline(center: Vertex, radius: (f32, f32)) -> Vec<Vertex> {
+    let mut path = primitives::ellipse_path(center, radius, 0.0, PI * 2.0, primitives::circle_segments(radius.0.max(radius.1)));
+    // the last point repeats the first one
+    path.pop();
+    path
+}
+
+impl Options for Shape {
+    fn get_options(&self) -> MenuOptions {
+        self.options
+    }
+}
+
+impl Draw for Shape {
+    fn draw(
+        &mut self,
+        menu: &mut Menu,
+        frame: &mut Frame
+    ) -> Result<(), MenuError> {
+        match self.thickness {
+            Some(thickness) => primitives::draw_colored_triangles(menu, frame, &self.outline(thickness)),
+            None => primitives::draw_triangles(menu, frame, &self.fill(), self.color),
+        }
+    }
+}
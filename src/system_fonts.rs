@@ -0,0 +1,365 @@
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::fs::File;
+use std::io::{ Read, Seek, SeekFrom };
+use std::path::{ Path, PathBuf };
+use std::sync::OnceLock;
+
+use crate::error::MenuError;
+
+// DejaVu Sans Bold, free to redistribute under the Bitstream Vera license in fonts/DejaVu-LICENSE.txt
+pub const DEFAULT_FONT: &[u8] = include_bytes!("../fonts/DejaVuSans-Bold.ttf");
+
+// how deep `scan` looks into the font directories, distributions nest them a few levels
+const MAX_DIRECTORY_DEPTH: usize = 4;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FontWeight {
+    #[default]
+    Regular,
+    Bold,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FontStyle {
+    #[default]
+    Normal,
+    // oblique faces count as italic
+    Italic,
+}
+
+// an installed font to look for, e.g. `FontQuery::new("Segoe UI").with_weight(FontWeight::Bold)`.
+// fontconfig answers on linux, everywhere else the font directories are searched
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontQuery {
+    pub family: String,
+    pub weight: FontWeight,
+    pub style: FontStyle,
+}
+
+// a font file found while searching the font directories
+struct FontFace {
+    path: PathBuf,
+    family: String,
+    subfamily: String,
+}
+
+impl FontQuery {
+    pub fn new(family: &str) -> Self {
+        Self {
+            family: family.to_string(),
+            .. Default::default()
+        }
+    }
+    pub fn with_weight(mut self, weight: FontWeight) -> Self {
+        self.weight = weight;
+        self
+    }
+    pub fn with_style(mut self, style: FontStyle) -> Self {
+        self.style = style;
+        self
+    }
+    // the file of the installed face closest to the weight and style asked for, None when no
+    // font of the family is installed
+    pub fn find(&self) -> Option<PathBuf> {
+        #[cfg(target_os = "linux")]
+        if let Some(path) = self.fontconfig() {
+            return Some(path)
+        }
+        self.scan()
+    }
+    // the bytes of `find`, ready for `rusttype::FontTexture::new` or `Overlay::create_menu`
+    pub fn load(&self) -> Result<Vec<u8>, MenuError> {
+        let path = self.find().ok_or_else(|| MenuError::FontNotFound(self.family.clone()))?;
+        std::fs::read(&path).map_err(|error| MenuError::FontFile(path, error))
+    }
+    // like `load`, with `DEFAULT_FONT` when the family isn't installed
+    pub fn load_or_default(&self) -> Cow<'static, [u8]> {
+        match self.load() {
+            Ok(bytes) => Cow::Owned(bytes),
+            Err(_) => Cow::Borrowed(DEFAULT_FONT),
+        }
+    }
+    // fc-match always answers with some font, the ones standing in for another family are refused
+    #[cfg(target_os = "linux")]
+    fn fontconfig(&self) -> Option<PathBuf> {
+        let mut pattern = self.family.clone();
+        if self.weight == FontWeight::Bold {
+            pattern.push_str(":bold");
+        }
+        if self.style == FontStyle::Italic {
+            pattern.push_str(":italic");
+        }
+
+        let output = std::process::Command::new("fc-match")
+            .arg("--format=%{family}\n%{file}")
+            .arg(&pattern)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None
+        }
+        let output = String::from_utf8(output.stdout).ok()?;
+        let (families, file) = output.split_once('\n')?;
+        families.split(',')
+            .any(|family| family.trim().eq_ignore_ascii_case(&self.family))
+            .then(|| PathBuf::from(file.trim()))
+    }
+    fn scan(&self) -> Option<PathBuf> {
+        self.best_face(installed_faces()).map(|face| face.path.clone())
+    }
+    // the face of the family matching the weight and style best
+    fn best_face<'a>(&self, faces: &'a [FontFace]) -> Option<&'a FontFace> {
+        faces.iter()
+            .filter(|face| face.family.eq_ignore_ascii_case(&self.family))
+            .max_by_key(|face| {
+                let (weight, style) = describe(&face.subfamily);
+                // "Regular" over "Condensed", "Light" or "Thin" when neither matches better
+                (weight == self.weight, style == self.style, is_plain(&face.subfamily), Reverse(face.subfamily.len()))
+            })
+    }
+}
+
+#[cfg(windows)]
+fn font_directories() -> Vec<PathBuf> {
+    let mut directories = Vec::new();
+    let windows = std::env::var_os("WINDIR").unwrap_or_else(|| "C:\\Windows".into());
+    directories.push(Path::new(&windows).join("Fonts"));
+    // fonts installed without admin rights
+    if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+        directories.push(Path::new(&local).join("Microsoft\\Windows\\Fonts"));
+    }
+    directories
+}
+
+#[cfg(not(windows))]
+fn font_directories() -> Vec<PathBuf> {
+    let mut directories = vec![ PathBuf::from("/usr/share/fonts"), PathBuf::from("/usr/local/share/fonts") ];
+    if let Some(home) = std::env::var_os("HOME") {
+        directories.push(Path::new(&home).join(".local/share/fonts"));
+        directories.push(Path::new(&home).join(".fonts"));
+    }
+    directories
+}
+
+// reads the names of every font in the font directories once, the file names say too little.
+// fonts installed while running are found after a restart
+fn installed_faces() -> &'static [FontFace] {
+    static FACES: OnceLock<Vec<FontFace>> = OnceLock::new();
+    FACES.get_or_init(|| {
+        let mut faces = Vec::new();
+        for directory in font_directories() {
+            collect_faces(&directory, MAX_DIRECTORY_DEPTH, &mut faces);
+        }
+        faces
+    })
+}
+
+fn collect_faces(directory: &Path, depth: usize, faces: &mut Vec<FontFace>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if depth > 0 {
+                collect_faces(&path, depth - 1, faces);
+            }
+            continue
+        }
+
+        let is_font = path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| matches!(extension.to_ascii_lowercase().as_str(), "ttf" | "otf" | "ttc"));
+        if !is_font {
+            continue
+        }
+        if let Some((family, subfamily)) = read_names(&path) {
+            faces.push(FontFace { path, family, subfamily });
+        }
+    }
+}
+
+// weight and style from a subfamily name like "Bold Italic"
+fn describe(subfamily: &str) -> (FontWeight, FontStyle) {
+    let subfamily = subfamily.to_ascii_lowercase();
+    let weight = if ["bold", "black", "heavy"].iter().any(|name| subfamily.contains(name)) {
+        FontWeight::Bold
+    } else {
+        FontWeight::Regular
+    };
+    let style = if subfamily.contains("italic") || subfamily.contains("oblique") {
+        FontStyle::Italic
+    } else {
+        FontStyle::Normal
+    };
+    (weight, style)
+}
+
+// true for subfamilies naming nothing but the weight and style, like "Regular" or "Bold Italic"
+fn is_plain(subfamily: &str) -> bool {
+    subfamily.to_ascii_lowercase()
+        .split_whitespace()
+        .all(|word| matches!(word, "regular" | "normal" | "book" | "roman" | "bold" | "italic" | "oblique"))
+}
+
+// family and subfamily from the `name` table of a TrueType or OpenType file, of the first
+// font of a collection. only the table directory and the names are read, not the glyphs,
+// and nothing is allocated for lengths that run past the end of the file
+fn read_names(path: &Path) -> Option<(String, String)> {
+    let mut file = File::open(path).ok()?;
+    let file_length = file.metadata().ok()?.len();
+
+    let mut header = [0; 12];
+    file.read_exact(&mut header).ok()?;
+    let mut start = 0;
+    if &header[0 .. 4] == b"ttcf" {
+        start = u64::from(read_u32(&header, 8)?);
+        file.seek(SeekFrom::Start(start)).ok()?;
+        file.read_exact(&mut header).ok()?;
+    }
+
+    let tables = usize::from(read_u16(&header, 4)?);
+    if start + 12 + tables as u64 * 16 > file_length {
+        return None
+    }
+    let mut directory = vec![0; tables * 16];
+    file.seek(SeekFrom::Start(start + 12)).ok()?;
+    file.read_exact(&mut directory).ok()?;
+    let (offset, length) = directory.chunks_exact(16)
+        .find(|record| &record[0 .. 4] == b"name")
+        .and_then(|record| Some((read_u32(record, 8)?, read_u32(record, 12)?)))?;
+
+    if u64::from(offset) + u64::from(length) > file_length {
+        return None
+    }
+    let mut table = vec![0; length as usize];
+    file.seek(SeekFrom::Start(u64::from(offset))).ok()?;
+    file.read_exact(&mut table).ok()?;
+    parse_names(&table)
+}
+
+fn parse_names(table: &[u8]) -> Option<(String, String)> {
+    let count = usize::from(read_u16(table, 2)?);
+    let strings = usize::from(read_u16(table, 4)?);
+
+    // by name id: 1 and 2 are the family and subfamily, 16 and 17 their typographic versions
+    // that keep every weight of a family together
+    let mut names: [Option<String>; 4] = Default::default();
+    for record in (0 .. count).map(|i| 6 + i * 12) {
+        let platform = read_u16(table, record)?;
+        let language = read_u16(table, record + 4)?;
+        let slot = match read_u16(table, record + 6)? {
+            1 => 0,
+            2 => 1,
+            16 => 2,
+            17 => 3,
+            _ => continue,
+        };
+        if names[slot].is_some() {
+            continue
+        }
+
+        let length = usize::from(read_u16(table, record + 8)?);
+        let offset = strings + usize::from(read_u16(table, record + 10)?);
+        let Some(bytes) = table.get(offset .. offset + length) else {
+            continue
+        };
+        names[slot] = match (platform, language) {
+            // unicode, and windows in american english
+            (0, _) | (3, 0x0409) => {
+                let units = bytes.chunks_exact(2).map(|unit| u16::from_be_bytes([unit[0], unit[1]]));
+                char::decode_utf16(units).collect::<Result<String, _>>().ok()
+            },
+            // macintosh roman in english, close enough to latin-1 for family names
+            (1, 0) => Some(bytes.iter().map(|byte| char::from(*byte)).collect()),
+            _ => None,
+        };
+    }
+
+    let [family, subfamily, typographic_family, typographic_subfamily] = names;
+    Some((typographic_family.or(family)?, typographic_subfamily.or(subfamily).unwrap_or_default()))
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at .. at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at .. at + 4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_subfamilies() {
+        assert_eq!(describe("Regular"), (FontWeight::Regular, FontStyle::Normal));
+        assert_eq!(describe("Bold Oblique"), (FontWeight::Bold, FontStyle::Italic));
+        assert_eq!(describe("Black Italic"), (FontWeight::Bold, FontStyle::Italic));
+        assert_eq!(describe("Light"), (FontWeight::Regular, FontStyle::Normal));
+
+        assert!(is_plain("Regular") && is_plain("Bold Italic") && is_plain(""));
+        assert!(!is_plain("Light") && !is_plain("Semibold") && !is_plain("Condensed Bold"));
+    }
+
+    #[test]
+    fn ranks_faces() {
+        let faces = ["Light", "Thin", "Regular", "Semibold", "Bold", "Bold Italic", "Condensed Italic"]
+            .iter()
+            .map(|subfamily| FontFace {
+                path: PathBuf::from(format!("{}.ttf", subfamily)),
+                family: String::from("Test Sans"),
+                subfamily: subfamily.to_string(),
+            })
+            .collect::<Vec<_>>();
+        let best = |query: FontQuery| query.best_face(&faces).map(|face| face.subfamily.as_str());
+
+        assert_eq!(best(FontQuery::new("test sans")), Some("Regular"));
+        assert_eq!(best(FontQuery::new("Test Sans").with_weight(FontWeight::Bold)), Some("Bold"));
+        let italic = FontQuery::new("Test Sans").with_style(FontStyle::Italic);
+        assert_eq!(best(italic.clone()), Some("Condensed Italic"));
+        assert_eq!(best(italic.with_weight(FontWeight::Bold)), Some("Bold Italic"));
+        assert_eq!(best(FontQuery::new("Other Sans")), None);
+    }
+
+    #[test]
+    fn reads_names_of_the_default_font() {
+        let path = std::env::temp_dir().join(format!("overlay-names-{}.ttf", std::process::id()));
+        std::fs::write(&path, DEFAULT_FONT).unwrap();
+        let names = read_names(&path);
+        _ = std::fs::remove_file(&path);
+        assert_eq!(names, Some((String::from("DejaVu Sans"), String::from("Bold"))));
+    }
+
+    #[test]
+    fn rejects_truncated_fonts() {
+        let path = std::env::temp_dir().join(format!("overlay-truncated-{}.ttf", std::process::id()));
+        std::fs::write(&path, &DEFAULT_FONT[.. 512]).unwrap();
+        let names = read_names(&path);
+        _ = std::fs::remove_file(&path);
+        assert_eq!(names, None);
+    }
+
+    #[test]
+    fn parses_name_tables() {
+        // a format 0 table with a windows family and subfamily record
+        let family = "Test Sans".encode_utf16().flat_map(u16::to_be_bytes).collect::<Vec<u8>>();
+        let subfamily = "Italic".encode_utf16().flat_map(u16::to_be_bytes).collect::<Vec<u8>>();
+        let mut table = Vec::new();
+        for value in [0u16, 2, 6 + 2 * 12] {
+            table.extend(value.to_be_bytes());
+        }
+        for (name, offset, length) in [(1u16, 0, family.len()), (2, family.len(), subfamily.len())] {
+            for value in [3u16, 1, 0x0409, name, length as u16, offset as u16] {
+                table.extend(value.to_be_bytes());
+            }
+        }
+        table.extend(&family);
+        table.extend(&subfamily);
+        assert_eq!(parse_names(&table), Some((String::from("Test Sans"), String::from("Italic"))));
+
+        assert_eq!(parse_names(&table[.. 4]), None);
+    }
+}
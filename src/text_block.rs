@@ -0,0 +1,355 @@
+use std::cell::RefCell;
+
+use glium::Frame;
+
+use crate::{ Menu, Rect, Vertex, Vec4, fonts, primitives, error::MenuError };
+
+// distance between baselines as a multiple of the text size
+pub const DEFAULT_LINE_SPACING: f32 = 1.2;
+
+const ELLIPSIS: &str = "\u{2026}";
+// for fonts without the ellipsis character, like the pre-baked ascii ones
+const ASCII_ELLIPSIS: &str = "...";
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum HAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum VAlign {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+// a paragraph of text laid out inside a rect
+#[derive(Clone, Debug)]
+pub struct TextBlock {
+    pub text: String,
+    // height of an em in pixels
+    pub size: f32,
+    pub color: Option<Vec4>,
+    // None draws with the menu's default font
+    pub font: Option<fonts::FontId>,
+    // breaks lines between words so they fit the rect's width
+    pub wrap: bool,
+    pub line_spacing: f32,
+    pub h_align: HAlign,
+    pub v_align: VAlign,
+    // lines past this are dropped and the last one kept ends in an ellipsis
+    pub max_lines: Option<usize>,
+    // cuts lines that are still wider than the rect short with an ellipsis
+    pub ellipsis: bool,
+    // the lines from the last layout, built again once anything they depend on changes
+    cache: RefCell<Option<TextBlockCache>>,
+}
+
+#[derive(Clone, Debug)]
+struct TextBlockCache {
+    text: String,
+    font: fonts::FontId,
+    size: f32,
+    width: f32,
+    wrap: bool,
+    max_lines: Option<usize>,
+    ellipsis: bool,
+    // every line with its width in pixels
+    lines: Vec<(String, f32)>,
+}
+
+impl TextBlockCache {
+    fn is_current(&self, block: &TextBlock, font: fonts::FontId, width: f32) -> bool {
+        self.text == block.text && self.font == font && self.size == block.size && self.width == width
+            && self.wrap == block.wrap && self.max_lines == block.max_lines && self.ellipsis == block.ellipsis
+    }
+}
+
+impl TextBlock {
+    pub fn new(text: &str, size: f32) -> Self {
+        Self {
+            text: String::from(text),
+            size,
+            color: None,
+            font: None,
+            wrap: true,
+            line_spacing: DEFAULT_LINE_SPACING,
+            h_align: HAlign::Left,
+            v_align: VAlign::Top,
+            max_lines: None,
+            ellipsis: true,
+            cache: RefCell::new(None),
+        }
+    }
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = Some(color);
+        self
+    }
+    pub fn with_font(mut self, font: fonts::FontId) -> Self {
+        self.font = Some(font);
+        self
+    }
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+    pub fn with_line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+    pub fn with_alignment(mut self, h_align: HAlign, v_align: VAlign) -> Self {
+        self.h_align = h_align;
+        self.v_align = v_align;
+        self
+    }
+    pub fn with_max_lines(mut self, max_lines: usize) -> Self {
+        self.max_lines = Some(max_lines);
+        self
+    }
+    pub fn with_ellipsis(mut self, ellipsis: bool) -> Self {
+        self.ellipsis = ellipsis;
+        self
+    }
+    fn font(&self, menu: &Menu) -> fonts::FontId {
+        self.font.unwrap_or(menu.fonts.default_font())
+    }
+    // runs `f` with the lines of a rect `width` pixels wide, laid out again first when the
+    // block or the width changed since the last time
+    fn with_layout<T>(&self, menu: &Menu, width: f32, f: impl FnOnce(&[(String, f32)]) -> T) -> T {
+        let font = self.font(menu);
+        let mut cache = self.cache.borrow_mut();
+        // the fields are public, so changes are noticed here
+        let layout = match cache.take() {
+            Some(layout) if layout.is_current(self, font, width) => layout,
+            _ => {
+                let measure = |line: &str| menu.fonts.text_width(font, line) * self.size;
+                let lines = self.layout(width, ellipsis(menu, font), &measure)
+                    .into_iter()
+                    .map(|line| {
+                        let line_width = measure(&line);
+                        (line, line_width)
+                    })
+                    .collect();
+                TextBlockCache {
+                    text: self.text.clone(),
+                    font,
+                    size: self.size,
+                    width,
+                    wrap: self.wrap,
+                    max_lines: self.max_lines,
+                    ellipsis: self.ellipsis,
+                    lines,
+                }
+            },
+        };
+        f(&cache.insert(layout).lines)
+    }
+    // the lines the text is drawn as in a rect `width` pixels wide
+    pub fn lines(&self, menu: &Menu, width: f32) -> Vec<String> {
+        self.with_layout(menu, width, |lines| lines.iter().map(|(line, _)| line.clone()).collect())
+    }
+    // `measure` is the width of a line in pixels
+    fn layout(&self, width: f32, ellipsis: &str, measure: impl Fn(&str) -> f32) -> Vec<String> {
+        let mut lines = wrap_lines(&self.text, if self.wrap { Some(width) } else { None }, &measure);
+
+        if let Some(max_lines) = self.max_lines {
+            if lines.len() > max_lines {
+                lines.truncate(max_lines);
+                if let Some(last) = lines.last_mut() {
+                    *last = ellipsize(last, width, ellipsis, &measure);
+                }
+            }
+        }
+        if self.ellipsis {
+            for line in lines.iter_mut() {
+                *line = truncate(line, width, ellipsis, &measure);
+            }
+        }
+        lines
+    }
+    // width and height of the laid out text, `width` limits it like a rect would
+    pub fn measure(&self, menu: &Menu, width: f32) -> (f32, f32) {
+        self.with_layout(menu, width, |lines| {
+            let widest = lines.iter().map(|(_, line_width)| *line_width).fold(0.0, f32::max);
+            (widest, self.height(lines.len()))
+        })
+    }
+    fn height(&self, lines: usize) -> f32 {
+        if lines == 0 {
+            return 0.0
+        }
+        self.size * self.line_spacing * (lines - 1) as f32 + self.size
+    }
+    pub fn draw(
+        &self,
+        rect: &Rect,
+        menu: &mut Menu,
+        frame: &mut Frame,
+    ) -> Result<(), MenuError> {
+        let menu = &*menu;
+        let font = self.font(menu);
+        let ascent = menu.fonts.get(font).ascent() * self.size;
+        let color = primitives::faded(menu, self.color.unwrap_or(menu.theme.text));
+
+        self.with_layout(menu, rect.width, |lines| -> Result<(), MenuError> {
+            let height = self.height(lines.len());
+            let top = rect.top_left.p[1] + match self.v_align {
+                VAlign::Top => 0.0,
+                VAlign::Middle => (rect.height - height) / 2.0,
+                VAlign::Bottom => rect.height - height,
+            };
+
+            for (i, (line, line_width)) in lines.iter().enumerate() {
+                let x = rect.top_left.p[0] + align_offset(self.h_align, rect.width, *line_width);
+                let baseline = top + ascent + self.size * self.line_spacing * i as f32;
+
+                // follows the rest of the menu while it slides
+                let baseline = Vertex { p: [ x + menu.offset.0, baseline + menu.offset.1 ] };
+                fonts::draw_text(menu, frame, font, line, baseline, self.size, color)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+// where a line `line_width` wide starts inside `width`
+pub fn align_offset(align: HAlign, width: f32, line_width: f32) -> f32 {
+    match align {
+        HAlign::Left => 0.0,
+        HAlign::Center => (width - line_width) / 2.0,
+        HAlign::Right => width - line_width,
+    }
+}
+
+// splits `text` at newlines and, with a `max_width`, between words so every line fits.
+// words wider than a whole line are broken between characters. `width` measures a line in
+// pixels, like `|line| menu.fonts.text_width(font, line) * size`
+pub fn wrap_lines(text: &str, max_width: Option<f32>, width: impl Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let max_width = match max_width {
+            Some(max_width) => max_width,
+            None => {
+                lines.push(paragraph.to_string());
+                continue
+            },
+        };
+
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if line.is_empty() || width(&candidate) <= max_width {
+                line = candidate;
+            } else {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            }
+
+            while line.chars().count() > 1 && width(&line) > max_width {
+                let split = line.char_indices()
+                    .skip(1)
+                    .map(|(index, _)| index)
+                    .take_while(|index| width(&line[.. *index]) <= max_width)
+                    .last()
+                    .unwrap_or_else(|| line.char_indices().nth(1).map_or(line.len(), |(index, _)| index));
+                let rest = line.split_off(split);
+                lines.push(std::mem::replace(&mut line, rest));
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// `line` as it is when it fits into `max_width`, cut short with `ellipsis` when it doesn't
+pub fn truncate(line: &str, max_width: f32, ellipsis: &str, width: impl Fn(&str) -> f32) -> String {
+    if width(line) <= max_width {
+        return line.to_string()
+    }
+    ellipsize(line, max_width, ellipsis, width)
+}
+
+// `line` with `ellipsis` at the end, dropping characters until both fit into `max_width`
+pub fn ellipsize(line: &str, max_width: f32, ellipsis: &str, width: impl Fn(&str) -> f32) -> String {
+    let mut kept = line.trim_end().to_string();
+    loop {
+        let candidate = format!("{}{}", kept, ellipsis);
+        if kept.is_empty() || width(&candidate) <= max_width {
+            return candidate
+        }
+        kept.pop();
+        kept.truncate(kept.trim_end().len());
+    }
+}
+
+// the ellipsis `font` can draw, three dots when it has no ellipsis character
+pub fn ellipsis(menu: &Menu, font: fonts::FontId) -> &'static str {
+    if menu.fonts.get(menu.fonts.resolve(font, '\u{2026}')).has_glyph('\u{2026}') {
+        ELLIPSIS
+    } else {
+        ASCII_ELLIPSIS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every character 10 pixels wide
+    fn measure(line: &str) -> f32 {
+        line.chars().count() as f32 * 10.0
+    }
+
+    #[test]
+    fn wraps_at_newlines() {
+        assert_eq!(wrap_lines("one\ntwo three", None, measure), [ "one", "two three" ]);
+        assert_eq!(wrap_lines("one\n\ntwo", Some(100.0), measure), [ "one", "", "two" ]);
+        assert_eq!(wrap_lines("", Some(100.0), measure), [ "" ]);
+    }
+
+    #[test]
+    fn wraps_between_words() {
+        // "one two" is exactly 70 pixels and stays on one line
+        assert_eq!(wrap_lines("one two three", Some(70.0), measure), [ "one two", "three" ]);
+        assert_eq!(wrap_lines("one two three", Some(69.0), measure), [ "one", "two", "three" ]);
+    }
+
+    #[test]
+    fn breaks_long_words() {
+        assert_eq!(wrap_lines("abcdefghij", Some(35.0), measure), [ "abc", "def", "ghi", "j" ]);
+        assert_eq!(wrap_lines("to abcdefgh", Some(40.0), measure), [ "to", "abcd", "efgh" ]);
+        // a line narrower than one character still gets a character per line
+        assert_eq!(wrap_lines("abc", Some(5.0), measure), [ "a", "b", "c" ]);
+    }
+
+    #[test]
+    fn truncates_with_an_ellipsis() {
+        assert_eq!(truncate("abcde", 50.0, "...", measure), "abcde");
+        assert_eq!(truncate("abcde", 49.0, "...", measure), "a...");
+        // trailing spaces go before the ellipsis is added
+        assert_eq!(ellipsize("ab cd", 60.0, "...", measure), "ab...");
+        // nothing fits, the ellipsis alone is left
+        assert_eq!(ellipsize("abc", 10.0, "...", measure), "...");
+    }
+
+    #[test]
+    fn limits_lines() {
+        let block = TextBlock::new("one two three four", 10.0).with_max_lines(2);
+        assert_eq!(block.layout(70.0, "...", measure), [ "one two", "thre..." ]);
+
+        let block = TextBlock::new("one two", 10.0).with_max_lines(2);
+        assert_eq!(block.layout(70.0, "...", measure), [ "one two" ]);
+    }
+
+    #[test]
+    fn keeps_lines_without_wrap_or_ellipsis() {
+        let block = TextBlock::new("a long line", 10.0).with_wrap(false);
+        assert_eq!(block.layout(60.0, "...", measure), [ "a l..." ]);
+
+        let block = block.with_ellipsis(false);
+        assert_eq!(block.layout(60.0, "...", measure), [ "a long line" ]);
+    }
+}
@@ -0,0 +1,103 @@
+use glium::Frame;
+
+use crate::{ Menu, Rect, Vec4, primitives, rusttype, error::MenuError };
+
+// directions the outline is drawn in for fonts without distance fields
+pub(crate) const OUTLINE_DIRECTIONS: [(f32, f32); 8] = [
+    (-1.0, -1.0), (0.0, -1.0), (1.0, -1.0),
+    (-1.0, 0.0), (1.0, 0.0),
+    (-1.0, 1.0), (0.0, 1.0), (1.0, 1.0),
+];
+
+// a line around the glyphs, sizes are in pixels
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextOutline {
+    pub color: Vec4,
+    pub width: f32,
+}
+
+// a copy of the text behind it, moved right and down by `offset`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextShadow {
+    pub color: Vec4,
+    pub offset: (f32, f32),
+    // how far its edge is blurred, only fonts loaded with `FontRegistry::load_sdf_bytes` blur
+    pub blur: f32,
+}
+
+// a rounded rect filled in behind the text
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextPlate {
+    pub color: Vec4,
+    // space between the text and the edge of the plate
+    pub padding: f32,
+    pub rounding: f32,
+}
+
+// how text stands out from whatever is behind the overlay. fonts loaded with
+// `FontRegistry::load_sdf_bytes` draw outlines and shadows in one pass and blur shadows,
+// others draw copies of the text, so keep outlines thin with those
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TextStyle {
+    // None draws with the theme's text color
+    pub color: Option<Vec4>,
+    pub outline: Option<TextOutline>,
+    pub shadow: Option<TextShadow>,
+    pub plate: Option<TextPlate>,
+}
+
+impl TextStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_color(mut self, color: Vec4) -> Self {
+        self.color = Some(color);
+        self
+    }
+    pub fn with_outline(mut self, color: Vec4, width: f32) -> Self {
+        self.outline = Some(TextOutline { color, width });
+        self
+    }
+    pub fn with_shadow(mut self, color: Vec4, offset: (f32, f32), blur: f32) -> Self {
+        self.shadow = Some(TextShadow { color, offset, blur });
+        self
+    }
+    pub fn with_plate(mut self, color: Vec4, padding: f32, rounding: f32) -> Self {
+        self.plate = Some(TextPlate { color, padding, rounding });
+        self
+    }
+    // a dark outline and shadow that keep light text readable over bright scenes
+    pub fn legible(menu: &Menu) -> Self {
+        let dark = Vec4::new(0.0, 0.0, 0.0, 0.85);
+        Self::new()
+            .with_color(menu.theme.text)
+            .with_outline(dark, 1.0)
+            .with_shadow(dark, (1.0, 1.0), 1.0)
+    }
+    // fills the plate around `bounds`, the pixels the text covers
+    pub fn draw_plate(&self, menu: &Menu, frame: &mut Frame, bounds: &Rect) -> Result<(), MenuError> {
+        match &self.plate {
+            Some(plate) => {
+                primitives::fill_rounded_rect(menu, frame, &bounds.grow(plate.padding), [plate.rounding; 4], plate.color)
+            },
+            None => Ok(()),
+        }
+    }
+    // the outline and shadow in EMs of text `size` pixels high, faded with the menu
+    pub(crate) fn effects(&self, menu: &Menu, size: f32) -> rusttype::TextEffects {
+        let color = |color: Vec4| primitives::faded(menu, color).v.into();
+        let size = size.max(f32::EPSILON);
+
+        let mut effects = rusttype::TextEffects::default();
+        if let Some(outline) = &self.outline {
+            effects.outline_color = color(outline.color);
+            effects.outline_width = outline.width / size;
+        }
+        if let Some(shadow) = &self.shadow {
+            effects.shadow_color = color(shadow.color);
+            effects.shadow_offset = (shadow.offset.0 / size, shadow.offset.1 / size);
+            effects.shadow_softness = shadow.blur / size;
+        }
+        effects
+    }
+}
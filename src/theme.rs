@@ -0,0 +1,270 @@
+use std::io::{ Error, ErrorKind };
+use std::path::Path;
+
+use crate::{ Vec4, primitives, fill };
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub panel: Vec4,
+    // when set panels and widgets fade from their color at the top to this one at the bottom
+    pub panel_gradient: Option<Vec4>,
+    pub widget: Vec4,
+    pub widget_gradient: Option<Vec4>,
+    pub accent: Vec4,
+    pub text: Vec4,
+    pub border: Vec4,
+    pub hover: Vec4,
+    pub active: Vec4,
+    pub disabled: Vec4,
+    pub padding: f32,
+    pub rounding: f32,
+    pub border_width: f32,
+    pub hover_width: f32,
+    pub shadow_color: Vec4,
+    pub shadow_size: f32,
+    pub shadow_offset: f32,
+    pub font_size: f32,
+    pub small_font_size: f32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::classic()
+    }
+}
+
+impl Theme {
+    // the look the menu had before themes existed
+    pub fn classic() -> Self {
+        Self {
+            panel: Vec4::new(0.5, 0.5, 0.5, 1.0),
+            panel_gradient: None,
+            widget: Vec4::new(0.0, 1.0, 0.7, 1.0),
+            widget_gradient: None,
+            accent: Vec4::new(1.0, 0.0, 0.0, 1.0),
+            text: Vec4::new(1.0, 1.0, 0.0, 1.0),
+            border: Vec4::new(1.0, 0.0, 1.0, 1.0),
+            hover: Vec4::new(1.0, 0.0, 0.0, 1.0),
+            active: Vec4::new(1.0, 0.5, 0.0, 1.0),
+            disabled: Vec4::new(0.3, 0.3, 0.3, 1.0),
+            padding: 6.0,
+            rounding: 0.0,
+            border_width: 4.0,
+            hover_width: 4.0,
+            shadow_color: Vec4::new(0.0, 0.0, 0.0, 0.0),
+            shadow_size: 0.0,
+            shadow_offset: 0.0,
+            font_size: 20.0,
+            small_font_size: 16.0,
+        }
+    }
+    pub fn dark() -> Self {
+        Self {
+            panel: Vec4::new(0.11, 0.11, 0.13, 0.95),
+            panel_gradient: Some(Vec4::new(0.07, 0.07, 0.09, 0.95)),
+            widget: Vec4::new(0.22, 0.22, 0.26, 1.0),
+            widget_gradient: Some(Vec4::new(0.17, 0.17, 0.20, 1.0)),
+            accent: Vec4::new(0.26, 0.59, 0.98, 1.0),
+            text: Vec4::new(0.92, 0.92, 0.92, 1.0),
+            border: Vec4::new(0.35, 0.35, 0.40, 1.0),
+            hover: Vec4::new(0.26, 0.59, 0.98, 0.8),
+            active: Vec4::new(0.06, 0.53, 0.98, 1.0),
+            disabled: Vec4::new(0.45, 0.45, 0.45, 1.0),
+            padding: 8.0,
+            rounding: 4.0,
+            border_width: 1.0,
+            hover_width: 2.0,
+            shadow_color: Vec4::new(0.0, 0.0, 0.0, 0.5),
+            shadow_size: 12.0,
+            shadow_offset: 4.0,
+            font_size: 18.0,
+            small_font_size: 14.0,
+        }
+    }
+    pub fn light() -> Self {
+        Self {
+            panel: Vec4::new(0.94, 0.94, 0.94, 0.95),
+            panel_gradient: Some(Vec4::new(0.88, 0.88, 0.90, 0.95)),
+            widget: Vec4::new(0.82, 0.82, 0.85, 1.0),
+            widget_gradient: Some(Vec4::new(0.76, 0.76, 0.80, 1.0)),
+            accent: Vec4::new(0.10, 0.45, 0.85, 1.0),
+            text: Vec4::new(0.08, 0.08, 0.08, 1.0),
+            border: Vec4::new(0.60, 0.60, 0.65, 1.0),
+            hover: Vec4::new(0.10, 0.45, 0.85, 0.8),
+            active: Vec4::new(0.05, 0.35, 0.75, 1.0),
+            disabled: Vec4::new(0.65, 0.65, 0.65, 1.0),
+            padding: 8.0,
+            rounding: 4.0,
+            border_width: 1.0,
+            hover_width: 2.0,
+            shadow_color: Vec4::new(0.0, 0.0, 0.0, 0.25),
+            shadow_size: 10.0,
+            shadow_offset: 3.0,
+            font_size: 18.0,
+            small_font_size: 14.0,
+        }
+    }
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "classic" => Some(Theme::classic()),
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            _ => None,
+        }
+    }
+    // shadow drawn under panels, none when `shadow_size` is zero
+    pub fn panel_shadow(&self) -> Option<primitives::Shadow> {
+        if self.shadow_size > 0.0 {
+            Some(primitives::Shadow::new((self.shadow_offset, self.shadow_offset), self.shadow_size, self.shadow_color))
+        } else {
+            None
+        }
+    }
+    // a widget's own color wins, otherwise the theme's widget color and gradient
+    pub fn widget_fill(&self, color: Option<Vec4>) -> fill::Fill {
+        match (color, self.widget_gradient) {
+            (Some(color), _) => fill::Fill::Solid(color),
+            (None, Some(bottom)) => fill::Fill::vertical(self.widget, bottom),
+            (None, None) => fill::Fill::Solid(self.widget),
+        }
+    }
+    pub fn panel_fill(&self, color: Option<Vec4>) -> fill::Fill {
+        match (color, self.panel_gradient) {
+            (Some(color), _) => fill::Fill::Solid(color),
+            (None, Some(bottom)) => fill::Fill::vertical(self.panel, bottom),
+            (None, None) => fill::Fill::Solid(self.panel),
+        }
+    }
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let source = std::fs::read_to_string(path)?;
+        Theme::parse(&source)
+    }
+    // one `key = value` per line, colors are `r, g, b, a` floats or `#rrggbb` / `#rrggbbaa` hex,
+    // gradients also take `none`.
+    // `base = dark` starts from a built in theme, lines starting with `;` or `//` are comments.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let mut theme = Theme::default();
+
+        for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with("//") {
+                continue
+            }
+            let invalid = |message: &str| Error::new(
+                ErrorKind::InvalidData,
+                format!("theme line {}: {}", number + 1, message)
+            );
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(invalid("expected `key = value`")),
+            };
+
+            match key {
+                "base" => theme = Theme::by_name(value).ok_or_else(|| invalid("unknown base theme"))?,
+                "panel" => theme.panel = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "panel_gradient" => theme.panel_gradient = parse_optional_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "widget" => theme.widget = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "widget_gradient" => theme.widget_gradient = parse_optional_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "accent" => theme.accent = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "text" => theme.text = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "border" => theme.border = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "hover" => theme.hover = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "active" => theme.active = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "disabled" => theme.disabled = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "padding" => theme.padding = value.parse().map_err(|_| invalid("invalid number"))?,
+                "rounding" => theme.rounding = value.parse().map_err(|_| invalid("invalid number"))?,
+                "border_width" => theme.border_width = value.parse().map_err(|_| invalid("invalid number"))?,
+                "hover_width" => theme.hover_width = value.parse().map_err(|_| invalid("invalid number"))?,
+                "shadow_color" => theme.shadow_color = parse_color(value).ok_or_else(|| invalid("invalid color"))?,
+                "shadow_size" => theme.shadow_size = value.parse().map_err(|_| invalid("invalid number"))?,
+                "shadow_offset" => theme.shadow_offset = value.parse().map_err(|_| invalid("invalid number"))?,
+                "font_size" => theme.font_size = value.parse().map_err(|_| invalid("invalid number"))?,
+                "small_font_size" => theme.small_font_size = value.parse().map_err(|_| invalid("invalid number"))?,
+                _ => return Err(invalid("unknown key")),
+            }
+        }
+
+        Ok(theme)
+    }
+}
+
+fn parse_optional_color(value: &str) -> Option<Option<Vec4>> {
+    if value.eq_ignore_ascii_case("none") {
+        Some(None)
+    } else {
+        parse_color(value).map(Some)
+    }
+}
+
+fn parse_color(value: &str) -> Option<Vec4> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok().map(|c| c as f32 / 255.0);
+        return match hex.len() {
+            6 => Some(Vec4::new(channel(0)?, channel(2)?, channel(4)?, 1.0)),
+            8 => Some(Vec4::new(channel(0)?, channel(2)?, channel(4)?, channel(6)?)),
+            _ => None,
+        }
+    }
+
+    let channels = value.split(',')
+        .map(|c| c.trim().parse::<f32>().ok())
+        .collect::<Option<Vec<f32>>>()?;
+
+    match channels[..] {
+        [r, g, b] => Some(Vec4::new(r, g, b, 1.0)),
+        [r, g, b, a] => Some(Vec4::new(r, g, b, a)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(parse_color("0.5, 0.25, 1"), Some(Vec4::new(0.5, 0.25, 1.0, 1.0)));
+        assert_eq!(parse_color("0, 0, 0, 0.5"), Some(Vec4::new(0.0, 0.0, 0.0, 0.5)));
+        assert_eq!(parse_color("#ff0000"), Some(Vec4::new(1.0, 0.0, 0.0, 1.0)));
+        assert_eq!(parse_color("#00ff0000"), Some(Vec4::new(0.0, 1.0, 0.0, 0.0)));
+        assert_eq!(parse_optional_color("None"), Some(None));
+    }
+
+    #[test]
+    fn rejects_malformed_colors() {
+        assert_eq!(parse_color("#ff00"), None);
+        assert_eq!(parse_color("#gg0000"), None);
+        assert_eq!(parse_color("1, 0"), None);
+        assert_eq!(parse_color("1, 0, 0, 0, 0"), None);
+        assert_eq!(parse_color("red"), None);
+        assert_eq!(parse_optional_color(""), None);
+    }
+
+    #[test]
+    fn parses_themes() {
+        let theme = Theme::parse("
+            ; comments and blank lines are skipped
+            base = dark
+            // so are these
+            accent = #336699
+            widget_gradient = none
+            padding = 6.5
+        ").unwrap();
+        assert_eq!(theme.accent, Vec4::new(0.2, 0.4, 0.6, 1.0));
+        assert_eq!(theme.widget_gradient, None);
+        assert_eq!(theme.padding, 6.5);
+        assert_eq!(theme.text, Theme::dark().text);
+        assert_eq!(Theme::parse("").unwrap(), Theme::classic());
+    }
+
+    #[test]
+    fn rejects_malformed_themes() {
+        for source in ["padding", "padding = wide", "text = #12", "base = neon", "unknown = 1"] {
+            let error = Theme::parse(source).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::InvalidData);
+        }
+        let error = Theme::parse("padding = 1\nmargin = 2").unwrap_err();
+        assert!(error.to_string().starts_with("theme line 2:"));
+    }
+}
@@ -0,0 +1,139 @@
+use windows::Win32::Foundation::{ HWND, RECT };
+
+use crate::{ windows_api, error::MenuError };
+
+// a top level window and what it was matched on
+#[derive(Clone, Debug)]
+pub struct WindowInfo {
+    pub hwnd: HWND,
+    pub title: String,
+    pub class: String,
+    pub pid: u32,
+    // None when the process couldn't be opened
+    pub exe: Option<String>,
+    // outer rect and client area, both in screen coordinates
+    pub rect: RECT,
+    pub client_rect: RECT,
+    pub visible: bool,
+}
+
+impl WindowInfo {
+    pub fn new(hwnd: HWND) -> Self {
+        let pid = windows_api::window_pid(hwnd);
+        Self {
+            hwnd,
+            title: windows_api::window_title(hwnd),
+            class: windows_api::window_class(hwnd),
+            pid,
+            exe: windows_api::process_exe_name(pid),
+            rect: windows_api::grab_window_dimensions(hwnd),
+            client_rect: windows_api::grab_client_rect(hwnd),
+            visible: windows_api::is_window_visible(hwnd),
+        }
+    }
+}
+
+pub enum TitleMatch {
+    Exact(String),
+    // case insensitive
+    Contains(String),
+    Regex(regex::Regex),
+}
+
+impl TitleMatch {
+    pub fn matches(&self, title: &str) -> bool {
+        match self {
+            TitleMatch::Exact(exact) => title == exact,
+            TitleMatch::Contains(part) => title.to_lowercase().contains(&part.to_lowercase()),
+            TitleMatch::Regex(regex) => regex.is_match(title),
+        }
+    }
+}
+
+// every criteria that is set has to match, with none set every visible window does
+#[derive(Default)]
+pub struct WindowFinder {
+    title: Option<TitleMatch>,
+    class: Option<String>,
+    exe: Option<String>,
+    pid: Option<u32>,
+    include_hidden: bool,
+}
+
+impl WindowFinder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(TitleMatch::Exact(title.to_string()));
+        self
+    }
+    pub fn title_contains(mut self, part: &str) -> Self {
+        self.title = Some(TitleMatch::Contains(part.to_string()));
+        self
+    }
+    pub fn title_regex(mut self, regex: regex::Regex) -> Self {
+        self.title = Some(TitleMatch::Regex(regex));
+        self
+    }
+    // exact window class, e.g. `SDL_app`
+    pub fn class(mut self, class: &str) -> Self {
+        self.class = Some(class.to_string());
+        self
+    }
+    // executable name compared case insensitively, e.g. `cs2.exe`
+    pub fn exe(mut self, exe: &str) -> Self {
+        self.exe = Some(exe.to_string());
+        self
+    }
+    pub fn pid(mut self, pid: u32) -> Self {
+        self.pid = Some(pid);
+        self
+    }
+    // hidden windows are skipped unless this is set
+    pub fn include_hidden(mut self, include_hidden: bool) -> Self {
+        self.include_hidden = include_hidden;
+        self
+    }
+    // every matching window in z order, topmost first
+    pub fn find_all(&self) -> Vec<WindowInfo> {
+        windows_api::enumerate_windows()
+            .into_iter()
+            .filter(|hwnd| self.include_hidden || windows_api::is_window_visible(*hwnd))
+            // cheap checks before opening the process for its exe name
+            .filter(|hwnd| self.pid.is_none_or(|pid| windows_api::window_pid(*hwnd) == pid))
+            .filter(|hwnd| self.class.as_ref().is_none_or(|class| windows_api::window_class(*hwnd) == *class))
+            .filter(|hwnd| self.title.as_ref().is_none_or(|title| title.matches(&windows_api::window_title(*hwnd))))
+            .map(WindowInfo::new)
+            .filter(|info| self.exe.as_ref().is_none_or(|exe| {
+                info.exe.as_ref().is_some_and(|name| name.eq_ignore_ascii_case(exe))
+            }))
+            .collect()
+    }
+    pub fn find(&self) -> Option<WindowInfo> {
+        self.find_all().into_iter().next()
+    }
+    // like `find` but with an error naming what was searched for
+    pub fn try_find(&self) -> Result<WindowInfo, MenuError> {
+        self.find().ok_or_else(|| MenuError::WindowNotFound(self.describe()))
+    }
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        match &self.title {
+            Some(TitleMatch::Exact(title)) => parts.push(format!("title {}", title)),
+            Some(TitleMatch::Contains(part)) => parts.push(format!("title containing {}", part)),
+            Some(TitleMatch::Regex(regex)) => parts.push(format!("title matching {}", regex.as_str())),
+            None => (),
+        }
+        if let Some(class) = &self.class {
+            parts.push(format!("class {}", class));
+        }
+        if let Some(exe) = &self.exe {
+            parts.push(format!("exe {}", exe));
+        }
+        if let Some(pid) = self.pid {
+            parts.push(format!("pid {}", pid));
+        }
+        parts.join(", ")
+    }
+}
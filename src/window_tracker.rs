@@ -0,0 +1,141 @@
+use std::time::{ Duration, Instant };
+
+use windows::Win32::Foundation::{ HWND, RECT };
+
+use crate::windows_api;
+
+// how often the target window is queried by default
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(50);
+
+// changes to the target window, in the order they were noticed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TargetEvent {
+    // new top left of the client area in screen pixels
+    Moved { x: i32, y: i32 },
+    // new client area size in physical pixels
+    Resized { width: u32, height: u32 },
+    Minimized,
+    Restored,
+    FocusLost,
+    FocusGained,
+    // the target is gone, tracking stops after this
+    Closed,
+}
+
+// keeps the overlay window on top of the target window's client area
+pub struct WindowTracker {
+    target: HWND,
+    overlay: HWND,
+    rect: RECT,
+    minimized: bool,
+    focused: bool,
+    closed: bool,
+    visible: bool,
+    // hide the overlay while another window is in the foreground
+    pub hide_when_unfocused: bool,
+    interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+impl WindowTracker {
+    pub fn new(target: HWND, overlay: HWND) -> Self {
+        Self {
+            target,
+            overlay,
+            rect: windows_api::grab_client_rect(target),
+            minimized: windows_api::is_minimized(target),
+            focused: true,
+            closed: false,
+            visible: true,
+            hide_when_unfocused: true,
+            interval: DEFAULT_INTERVAL,
+            last_poll: None,
+        }
+    }
+    // zero polls every time `update` is called
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+    pub fn target(&self) -> HWND {
+        self.target
+    }
+    // the target's client area in screen coordinates as of the last poll
+    pub fn rect(&self) -> RECT {
+        self.rect
+    }
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+    // re-queries the target once the interval has passed, follows it with the overlay and
+    // returns what changed
+    pub fn update(&mut self) -> Vec<TargetEvent> {
+        let mut events = Vec::new();
+        if self.closed {
+            return events
+        }
+
+        let now = Instant::now();
+        if let Some(last_poll) = self.last_poll {
+            if now.duration_since(last_poll) < self.interval {
+                return events
+            }
+        }
+        self.last_poll = Some(now);
+
+        if !windows_api::window_exists(self.target) {
+            self.closed = true;
+            self.set_visible(false);
+            events.push(TargetEvent::Closed);
+            return events
+        }
+
+        let minimized = windows_api::is_minimized(self.target);
+        if minimized != self.minimized {
+            self.minimized = minimized;
+            events.push(if minimized { TargetEvent::Minimized } else { TargetEvent::Restored });
+        }
+
+        // clicking into the menu makes the overlay the foreground window, that still counts
+        let foreground = windows_api::foreground_window();
+        let focused = foreground == self.target || foreground == self.overlay;
+        if focused != self.focused {
+            self.focused = focused;
+            events.push(if focused { TargetEvent::FocusGained } else { TargetEvent::FocusLost });
+        }
+
+        // a minimized window reports a client rect somewhere off screen, keep the last real one
+        if !minimized {
+            let rect = windows_api::grab_client_rect(self.target);
+            let moved = rect.left != self.rect.left || rect.top != self.rect.top;
+            let resized = rect.right - rect.left != self.rect.right - self.rect.left
+                || rect.bottom - rect.top != self.rect.bottom - self.rect.top;
+
+            if moved || resized {
+                self.rect = rect;
+                windows_api::move_window(self.overlay, rect);
+            }
+            if moved {
+                events.push(TargetEvent::Moved { x: rect.left, y: rect.top });
+            }
+            if resized {
+                events.push(TargetEvent::Resized {
+                    width: (rect.right - rect.left).max(0) as u32,
+                    height: (rect.bottom - rect.top).max(0) as u32,
+                });
+            }
+        }
+
+        self.set_visible(!minimized && (self.focused || !self.hide_when_unfocused));
+
+        events
+    }
+    fn set_visible(&mut self, visible: bool) {
+        if visible != self.visible {
+            self.visible = visible;
+            windows_api::set_window_visible(self.overlay, visible);
+        }
+    }
+}
@@ -6,15 +6,37 @@ use windows::{
         WS_EX_LAYERED,
         WS_EX_TRANSPARENT,
         GetWindowRect,
-        FindWindowA
+        GetClientRect,
+        FindWindowA,
+        IsWindow,
+        IsIconic,
+        GetForegroundWindow,
+        SetWindowPos,
+        ShowWindow,
+        HWND_TOPMOST,
+        SWP_NOACTIVATE,
+        SW_HIDE,
+        SW_SHOWNOACTIVATE,
+        EnumWindows,
+        GetWindowTextW,
+        GetClassNameW,
+        GetWindowThreadProcessId,
+        IsWindowVisible
     },
-    Win32::Foundation:: { HWND, RECT },
+    Win32::Foundation:: { HWND, RECT, HANDLE, POINT, LPARAM, CloseHandle, GlobalFree },
+    Win32::System::Threading::{ OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION },
+    Win32::Graphics::Gdi::{ ClientToScreen, EnumDisplayMonitors, HMONITOR, HDC },
+    Win32::UI::HiDpi::GetDpiForWindow,
+    Win32::System::DataExchange::{ OpenClipboard, EmptyClipboard, SetClipboardData, CloseClipboard },
+    Win32::System::Memory::{ GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE },
 };
 use winit::raw_window_handle::{ Win32WindowHandle, RawWindowHandle };
 use winit::platform;
-use windows_core::PCSTR;
+use windows_core::{ PCSTR, PWSTR, BOOL };
 use std::ffi::CString;
 
+use crate::error::MenuError;
+
 pub fn grab_handle(window_handle: winit::raw_window_handle::WindowHandle) -> Option<platform::windows::HWND> {
     let raw_handle = window_handle.as_raw();
 
@@ -30,11 +52,14 @@ pub fn grab_handle(window_handle: winit::raw_window_handle::WindowHandle) -> Opt
 }
 
 pub fn grab_game_hwnd(input: &str) -> HWND {
-    let window_name = CString::new(input).unwrap();
+    try_grab_game_hwnd(input).expect("Process Not Open!")
+}
 
-    let hwnd = unsafe { FindWindowA(None, PCSTR::from_raw(window_name.as_ptr() as _))
-                        .expect("Process Not Open!") };
-    hwnd
+pub fn try_grab_game_hwnd(input: &str) -> Result<HWND, MenuError> {
+    let window_name = CString::new(input).map_err(|_| MenuError::WindowNotFound(input.to_string()))?;
+
+    unsafe { FindWindowA(None, PCSTR::from_raw(window_name.as_ptr() as _)) }
+        .map_err(|_| MenuError::WindowNotFound(input.to_string()))
 }
 
 pub fn make_window_click_through(hwnd: HWND) {
@@ -59,3 +84,161 @@ pub fn grab_window_dimensions(hwnd: HWND) -> RECT {
         return window_dimensions
     }
 }
+
+// the area inside the window's borders and title bar, in screen coordinates
+pub fn grab_client_rect(hwnd: HWND) -> RECT {
+    unsafe {
+        let mut client: RECT = std::mem::zeroed();
+        _ = GetClientRect(hwnd, &mut client);
+        let mut origin = POINT { x: 0, y: 0 };
+        _ = ClientToScreen(hwnd, &mut origin);
+        RECT {
+            left: origin.x,
+            top: origin.y,
+            right: origin.x + client.right - client.left,
+            bottom: origin.y + client.bottom - client.top,
+        }
+    }
+}
+
+// every top level window, visible or not
+pub fn enumerate_windows() -> Vec<HWND> {
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = unsafe { &mut *(lparam.0 as *mut Vec<HWND>) };
+        windows.push(hwnd);
+        // keep enumerating
+        BOOL(1)
+    }
+
+    let mut windows: Vec<HWND> = Vec::new();
+    unsafe {
+        _ = EnumWindows(Some(collect), LPARAM(&mut windows as *mut Vec<HWND> as isize));
+    }
+    windows
+}
+
+// the rect of every monitor in screen coordinates, in the order windows enumerates them
+pub fn monitor_rects() -> Vec<RECT> {
+    unsafe extern "system" fn collect(_monitor: HMONITOR, _hdc: HDC, rect: *mut RECT, lparam: LPARAM) -> BOOL {
+        let rects = unsafe { &mut *(lparam.0 as *mut Vec<RECT>) };
+        if !rect.is_null() {
+            rects.push(unsafe { *rect });
+        }
+        BOOL(1)
+    }
+
+    let mut rects: Vec<RECT> = Vec::new();
+    unsafe {
+        _ = EnumDisplayMonitors(None, None, Some(collect), LPARAM(&mut rects as *mut Vec<RECT> as isize));
+    }
+    rects
+}
+
+pub fn window_title(hwnd: HWND) -> String {
+    let mut buffer = [0u16; 512];
+    let length = unsafe { GetWindowTextW(hwnd, &mut buffer) };
+    String::from_utf16_lossy(&buffer[..length.max(0) as usize])
+}
+
+pub fn window_class(hwnd: HWND) -> String {
+    let mut buffer = [0u16; 256];
+    let length = unsafe { GetClassNameW(hwnd, &mut buffer) };
+    String::from_utf16_lossy(&buffer[..length.max(0) as usize])
+}
+
+pub fn window_pid(hwnd: HWND) -> u32 {
+    let mut pid = 0u32;
+    unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid as *mut u32)) };
+    pid
+}
+
+pub fn is_window_visible(hwnd: HWND) -> bool {
+    unsafe { IsWindowVisible(hwnd).as_bool() }
+}
+
+// executable name like `cs2.exe`, None when the process can't be opened. limited access is
+// granted for most protected and anti-cheat processes too
+pub fn process_exe_name(pid: u32) -> Option<String> {
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 1024];
+        let mut length = buffer.len() as u32;
+        let result = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut length);
+        _ = CloseHandle(process);
+        result.ok()?;
+        let path = String::from_utf16_lossy(&buffer[..length as usize]);
+        path.rsplit(['\\', '/']).next().map(str::to_string)
+    }
+}
+
+// false once the window has been closed
+pub fn window_exists(hwnd: HWND) -> bool {
+    unsafe { IsWindow(Some(hwnd)).as_bool() }
+}
+
+pub fn is_minimized(hwnd: HWND) -> bool {
+    unsafe { IsIconic(hwnd).as_bool() }
+}
+
+pub fn foreground_window() -> HWND {
+    unsafe { GetForegroundWindow() }
+}
+
+// moves and resizes the window to `rect` in screen coordinates, keeping it on top without stealing focus
+pub fn move_window(hwnd: HWND, rect: RECT) -> bool {
+    unsafe {
+        SetWindowPos(
+            hwnd,
+            Some(HWND_TOPMOST),
+            rect.left,
+            rect.top,
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+            SWP_NOACTIVATE
+        ).is_ok()
+    }
+}
+
+pub fn set_window_visible(hwnd: HWND, visible: bool) {
+    unsafe {
+        _ = ShowWindow(hwnd, if visible { SW_SHOWNOACTIVATE } else { SW_HIDE });
+    }
+}
+
+// 1.0 at 96 dpi, 1.5 on a 150% monitor
+pub fn grab_scale_factor(hwnd: HWND) -> f32 {
+    let dpi = unsafe { GetDpiForWindow(hwnd) };
+    if dpi == 0 {
+        return 1.0
+    }
+    dpi as f32 / 96.0
+}
+
+pub fn copy_to_clipboard(text: &str) -> bool {
+    // CF_UNICODETEXT wants a null terminated utf-16 string in global memory
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        if OpenClipboard(None).is_err() {
+            return false
+        }
+        let copied = (|| {
+            EmptyClipboard().ok()?;
+            let memory = GlobalAlloc(GMEM_MOVEABLE, wide.len() * std::mem::size_of::<u16>()).ok()?;
+            let destination = GlobalLock(memory) as *mut u16;
+            if destination.is_null() {
+                _ = GlobalFree(Some(memory));
+                return None
+            }
+            std::ptr::copy_nonoverlapping(wide.as_ptr(), destination, wide.len());
+            _ = GlobalUnlock(memory);
+            // 13 = CF_UNICODETEXT, the clipboard owns the memory once this succeeds
+            if SetClipboardData(13, Some(HANDLE(memory.0))).is_err() {
+                _ = GlobalFree(Some(memory));
+                return None
+            }
+            Some(())
+        })();
+        _ = CloseClipboard();
+        copied.is_some()
+    }
+}